@@ -0,0 +1,126 @@
+/*
+ * sysdirs - C interface
+ *
+ * Build the library with the `ffi` feature:
+ *
+ *     cargo rustc --release --lib --features ffi --crate-type staticlib
+ *     cargo rustc --release --lib --features ffi --crate-type cdylib
+ *
+ * Memory ownership: the library never allocates memory for the caller and never
+ * keeps pointers passed to it. Paths are copied into caller-owned buffers, and
+ * the strings returned by sysdirs_error_message() are static and must not be
+ * freed.
+ *
+ * Every function is safe to call from any thread.
+ */
+
+#ifndef SYSDIRS_H
+#define SYSDIRS_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * Directory kinds, in the same order as the Rust `Dir::ALL`.
+ */
+enum sysdirs_kind {
+	SYSDIRS_HOME = 0,
+	SYSDIRS_CACHE = 1,
+	SYSDIRS_CONFIG = 2,
+	SYSDIRS_CONFIG_LOCAL = 3,
+	SYSDIRS_DATA = 4,
+	SYSDIRS_DATA_LOCAL = 5,
+	SYSDIRS_EXECUTABLE = 6,
+	SYSDIRS_PREFERENCE = 7,
+	SYSDIRS_RUNTIME = 8,
+	SYSDIRS_STATE = 9,
+	SYSDIRS_AUDIO = 10,
+	SYSDIRS_DESKTOP = 11,
+	SYSDIRS_DOCUMENT = 12,
+	SYSDIRS_DOWNLOAD = 13,
+	SYSDIRS_FONT = 14,
+	SYSDIRS_PICTURE = 15,
+	SYSDIRS_PUBLIC = 16,
+	SYSDIRS_TEMPLATE = 17,
+	SYSDIRS_VIDEO = 18,
+	SYSDIRS_TEMP = 19,
+	SYSDIRS_LIBRARY = 20,
+};
+
+/*
+ * Error codes. Every error is negative.
+ */
+
+/* The home directory is unknown, e.g. $HOME or %USERPROFILE% is unset. */
+#define SYSDIRS_ERR_HOME_NOT_SET (-1)
+/* An environment variable the directory comes from is unset or empty. */
+#define SYSDIRS_ERR_ENV_VAR_NOT_SET (-2)
+/* An environment variable is set to something that isn't a usable path. */
+#define SYSDIRS_ERR_ENV_VAR_INVALID (-3)
+/* Android paths were never provided with sysdirs_init_android(). */
+#define SYSDIRS_ERR_NOT_INITIALIZED (-4)
+/* The platform has no such directory, e.g. SYSDIRS_LIBRARY on Linux. */
+#define SYSDIRS_ERR_UNSUPPORTED (-5)
+/* An unknown kind, or a NULL buffer with a non-zero length. */
+#define SYSDIRS_ERR_INVALID_ARGUMENT (-6)
+
+/*
+ * Looks up the directory `kind` (a `enum sysdirs_kind`).
+ *
+ * On success, returns the length of the path in bytes, not counting the
+ * terminating NUL. If `len` is greater than that length, the path and a NUL
+ * are written to `buf`. Otherwise the buffer is too small and is left
+ * untouched: call again with a buffer of at least the returned length + 1.
+ * Passing `buf = NULL, len = 0` only queries the length.
+ *
+ * On failure, returns one of the negative SYSDIRS_ERR_* codes and leaves
+ * `buf` untouched.
+ *
+ * Paths are the raw bytes on Unix, which need not be UTF-8, and UTF-8 on
+ * Windows.
+ *
+ *     char buf[256];
+ *     ptrdiff_t n = sysdirs_get(SYSDIRS_CACHE, buf, sizeof buf);
+ *     if (n < 0) {
+ *         fprintf(stderr, "%s\n", sysdirs_error_message(n));
+ *     } else if ((size_t)n >= sizeof buf) {
+ *         char *big = malloc((size_t)n + 1);
+ *         sysdirs_get(SYSDIRS_CACHE, big, (size_t)n + 1);
+ *     }
+ */
+ptrdiff_t sysdirs_get(int kind, char *buf, size_t len);
+
+/*
+ * Returns a static, NUL-terminated description of a SYSDIRS_ERR_* code.
+ * The string must not be freed.
+ */
+const char *sysdirs_error_message(ptrdiff_t code);
+
+#if defined(__ANDROID__)
+/*
+ * Initializes the Android paths from `Context.getFilesDir()`. Call once at
+ * startup, before any lookup. The string is copied.
+ *
+ * Returns 0, or SYSDIRS_ERR_INVALID_ARGUMENT if `files_dir` is NULL or not
+ * UTF-8.
+ */
+int sysdirs_init_android(const char *files_dir);
+
+/*
+ * Like sysdirs_init_android(), but also takes `Context.getCacheDir()`.
+ * Both strings are copied.
+ *
+ * Returns 0, or SYSDIRS_ERR_INVALID_ARGUMENT if either argument is NULL or
+ * not UTF-8.
+ */
+int sysdirs_init_android_with_cache(const char *files_dir, const char *cache_dir);
+#endif
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SYSDIRS_H */
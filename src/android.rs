@@ -5,7 +5,13 @@
 //!
 //! 1. Manual init via `init_android()` - for Kotlin/Java apps embedding Rust
 //! 2. Auto-detection via ndk-context - for pure Rust Android apps (requires `android-auto` feature)
+//!
+//! A `Resolver` can also supply both directories with `with_android_dirs()`, which is
+//! how Android is emulated on other hosts.
 
+use crate::error::unsupported;
+use crate::explain::{Found, Source};
+use crate::{DirError, Resolver};
 use std::path::PathBuf;
 use std::sync::OnceLock;
 
@@ -16,12 +22,14 @@ use std::sync::OnceLock;
 static ANDROID_FILES_DIR: OnceLock<PathBuf> = OnceLock::new();
 static ANDROID_CACHE_DIR: OnceLock<PathBuf> = OnceLock::new();
 
+#[cfg(target_os = "android")]
 pub fn init_android(files_dir: &str) {
 	let path = PathBuf::from(files_dir);
 	let _ = ANDROID_FILES_DIR.set(path.clone());
 	let _ = ANDROID_CACHE_DIR.set(path.join("cache"));
 }
 
+#[cfg(target_os = "android")]
 pub fn init_android_with_cache(files_dir: &str, cache_dir: &str) {
 	let _ = ANDROID_FILES_DIR.set(PathBuf::from(files_dir));
 	let _ = ANDROID_CACHE_DIR.set(PathBuf::from(cache_dir));
@@ -33,7 +41,7 @@ pub fn init_android_with_cache(files_dir: &str, cache_dir: &str) {
 
 /// Try to get files dir from ndk-context (for pure Rust Android apps).
 /// Returns None if ndk-context isn't initialized or feature isn't enabled.
-#[cfg(feature = "android-auto")]
+#[cfg(all(target_os = "android", feature = "android-auto"))]
 fn try_ndk_context_files_dir() -> Option<PathBuf> {
 	use jni::objects::{JObject, JString};
 
@@ -64,7 +72,7 @@ fn try_ndk_context_files_dir() -> Option<PathBuf> {
 }
 
 /// Try to get cache dir from ndk-context (for pure Rust Android apps).
-#[cfg(feature = "android-auto")]
+#[cfg(all(target_os = "android", feature = "android-auto"))]
 fn try_ndk_context_cache_dir() -> Option<PathBuf> {
 	use jni::objects::{JObject, JString};
 
@@ -94,127 +102,158 @@ fn try_ndk_context_cache_dir() -> Option<PathBuf> {
 // Helpers
 // =============================================================================
 
-fn files_dir() -> Option<PathBuf> {
+fn files_dir(r: &Resolver) -> Result<Found, DirError> {
+	// An explicit home override stands in for the files directory
+	if let Some(path) = r.home_override() {
+		return Ok(Found::new(path.to_path_buf(), Source::Injected));
+	}
+
 	// First check manual init
 	if let Some(path) = ANDROID_FILES_DIR.get() {
-		return Some(path.clone());
+		return Ok(Found::new(path.clone(), Source::AndroidInit));
 	}
 
 	// Then try ndk-context if feature is enabled
-	#[cfg(feature = "android-auto")]
+	#[cfg(all(target_os = "android", feature = "android-auto"))]
 	{
-		return try_ndk_context_files_dir();
+		return try_ndk_context_files_dir()
+			.map(|path| Found::new(path, Source::AndroidNdkContext))
+			.ok_or(DirError::NotInitialized);
 	}
 
-	#[cfg(not(feature = "android-auto"))]
-	None
+	#[cfg(not(all(target_os = "android", feature = "android-auto")))]
+	Err(DirError::NotInitialized)
 }
 
-fn cache() -> Option<PathBuf> {
+fn cache(r: &Resolver) -> Result<Found, DirError> {
+	if let Some(path) = r.android_cache_override() {
+		return Ok(Found::new(path.to_path_buf(), Source::Injected));
+	}
+
+	// Derived the same way init_android() does when home is overridden
+	if let Some(path) = r.home_override() {
+		return Ok(Found::new(path.join("cache"), Source::Injected));
+	}
+
 	// First check manual init
 	if let Some(path) = ANDROID_CACHE_DIR.get() {
-		return Some(path.clone());
+		return Ok(Found::new(path.clone(), Source::AndroidInit));
 	}
 
 	// Then try ndk-context if feature is enabled
-	#[cfg(feature = "android-auto")]
+	#[cfg(all(target_os = "android", feature = "android-auto"))]
 	{
-		return try_ndk_context_cache_dir();
+		return try_ndk_context_cache_dir()
+			.map(|path| Found::new(path, Source::AndroidNdkContext))
+			.ok_or(DirError::NotInitialized);
 	}
 
-	#[cfg(not(feature = "android-auto"))]
-	None
+	#[cfg(not(all(target_os = "android", feature = "android-auto")))]
+	Err(DirError::NotInitialized)
 }
 
 // =============================================================================
 // Directory implementations
 // =============================================================================
 
-pub fn home_dir() -> Option<PathBuf> {
-	files_dir()
+pub fn home_dir(r: &Resolver) -> Result<Found, DirError> {
+	files_dir(r)
 }
 
-pub fn cache_dir() -> Option<PathBuf> {
-	cache()
+pub fn cache_dir(r: &Resolver) -> Result<Found, DirError> {
+	cache(r)
 }
 
-pub fn config_dir() -> Option<PathBuf> {
-	files_dir()
+pub fn config_dir(r: &Resolver) -> Result<Found, DirError> {
+	files_dir(r)
 }
 
-pub fn config_local_dir() -> Option<PathBuf> {
-	files_dir()
+pub fn config_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	files_dir(r)
 }
 
-pub fn data_dir() -> Option<PathBuf> {
-	files_dir()
+pub fn data_dir(r: &Resolver) -> Result<Found, DirError> {
+	files_dir(r)
 }
 
-pub fn data_local_dir() -> Option<PathBuf> {
-	files_dir()
+pub fn data_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	files_dir(r)
 }
 
-pub fn executable_dir() -> Option<PathBuf> {
-	None
+pub fn executable_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn preference_dir() -> Option<PathBuf> {
-	files_dir()
+pub fn preference_dir(r: &Resolver) -> Result<Found, DirError> {
+	files_dir(r)
 }
 
-pub fn runtime_dir() -> Option<PathBuf> {
-	None
+pub fn runtime_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn state_dir() -> Option<PathBuf> {
-	None
+pub fn state_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
 // Android apps don't have access to user directories from native code
-pub fn audio_dir() -> Option<PathBuf> {
-	None
+pub fn audio_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn desktop_dir() -> Option<PathBuf> {
-	None
+pub fn desktop_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn document_dir() -> Option<PathBuf> {
-	None
+pub fn document_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn download_dir() -> Option<PathBuf> {
-	None
+pub fn download_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn font_dir() -> Option<PathBuf> {
-	None
+pub fn font_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn picture_dir() -> Option<PathBuf> {
-	None
+pub fn picture_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn public_dir() -> Option<PathBuf> {
-	None
+pub fn public_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn template_dir() -> Option<PathBuf> {
-	None
+pub fn template_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
+}
+
+pub fn video_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
+}
+
+// =============================================================================
+// System search paths
+// =============================================================================
+
+// The app sandbox has no shared system locations
+pub fn data_dirs(_r: &Resolver) -> Vec<PathBuf> {
+	Vec::new()
 }
 
-pub fn video_dir() -> Option<PathBuf> {
-	None
+pub fn config_dirs(_r: &Resolver) -> Vec<PathBuf> {
+	Vec::new()
 }
 
 // =============================================================================
 // sysdirs extensions
 // =============================================================================
 
-pub fn temp_dir() -> Option<PathBuf> {
-	files_dir().map(|f| f.join("tmp"))
+pub fn temp_dir(r: &Resolver) -> Result<Found, DirError> {
+	files_dir(r).map(|f| f.join("tmp"))
 }
 
-pub fn library_dir() -> Option<PathBuf> {
-	None
+pub fn library_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
@@ -0,0 +1,267 @@
+//! Application-scoped directories
+//!
+//! Builds on the base directory functions and applies each platform's naming
+//! convention for per-app subdirectories.
+
+use crate::RuntimeDir;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// =============================================================================
+// Naming conventions
+// =============================================================================
+
+/// Apple uses a reverse-DNS bundle identifier, e.g. `com.Foo-Corp.Bar-App`.
+#[cfg(any(
+	target_os = "macos",
+	target_os = "ios",
+	target_os = "tvos",
+	target_os = "watchos",
+	target_os = "visionos"
+))]
+fn app_path(qualifier: &str, organization: &str, application: &str) -> PathBuf {
+	let parts: Vec<String> = [qualifier, organization, application]
+		.iter()
+		.map(|part| part.trim().replace(' ', "-"))
+		.filter(|part| !part.is_empty())
+		.collect();
+	PathBuf::from(parts.join("."))
+}
+
+/// Windows nests the application under its organization, e.g. `Foo Corp\Bar App`.
+#[cfg(target_os = "windows")]
+fn app_path(_qualifier: &str, organization: &str, application: &str) -> PathBuf {
+	[organization, application]
+		.iter()
+		.map(|part| part.trim())
+		.filter(|part| !part.is_empty())
+		.collect()
+}
+
+/// The Android sandbox is already app-specific, so no subdirectory is used.
+#[cfg(target_os = "android")]
+fn app_path(_qualifier: &str, _organization: &str, _application: &str) -> PathBuf {
+	PathBuf::new()
+}
+
+/// XDG platforms use the lowercased application name without whitespace, e.g. `barapp`.
+#[cfg(not(any(
+	target_os = "macos",
+	target_os = "ios",
+	target_os = "tvos",
+	target_os = "watchos",
+	target_os = "visionos",
+	target_os = "windows",
+	target_os = "android"
+)))]
+fn app_path(_qualifier: &str, _organization: &str, application: &str) -> PathBuf {
+	let name: String = application.chars().filter(|c| !c.is_whitespace()).collect();
+	PathBuf::from(name.to_lowercase())
+}
+
+// =============================================================================
+// AppDirs
+// =============================================================================
+
+/// Directories scoped to a single application.
+///
+/// Each directory is the matching base directory (e.g. [`cache_dir()`](crate::cache_dir))
+/// joined with an application-specific path that follows the platform's naming convention:
+///
+/// |Platform | Convention                    | Example `cache_dir`                              |
+/// | ------- | ----------------------------- | ------------------------------------------------ |
+/// | Linux   | lowercase application name    | /home/alice/.cache/barapp                        |
+/// | macOS   | `qualifier.organization.app`  | /Users/Alice/Library/Caches/com.Foo-Corp.Bar-App |
+/// | Windows | `organization\app\{kind}`     | C:\Users\Alice\AppData\Local\Foo Corp\Bar App\cache |
+/// | iOS     | `qualifier.organization.app`  | &lt;sandbox&gt;/Library/Caches/com.Foo-Corp.Bar-App |
+/// | Android | sandbox is already app-specific | /data/data/com.example/files/cache             |
+/// | WASM    | `None`                        |                                                  |
+///
+/// On Windows, `{kind}` is `cache`, `config` or `data`, so that roaming and local
+/// data for the same application don't collide.
+///
+/// # Example
+///
+/// ```rust
+/// use sysdirs::AppDirs;
+///
+/// let app = AppDirs::new("com", "Foo Corp", "Bar App");
+/// app.config_dir();
+/// // Lin: Some(/home/alice/.config/barapp)
+/// // Win: Some(C:\Users\Alice\AppData\Roaming\Foo Corp\Bar App\config)
+/// // Mac: Some(/Users/Alice/Library/Application Support/com.Foo-Corp.Bar-App)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppDirs {
+	app_path: PathBuf,
+}
+
+impl AppDirs {
+	/// Creates the directories for an application.
+	///
+	/// - `qualifier` is the reverse domain name suffix, e.g. `"com"` or `"org"`
+	/// - `organization` is the name of the organization that develops the app
+	/// - `application` is the name of the application itself
+	///
+	/// Empty components are skipped where the platform convention uses them.
+	pub fn new(qualifier: &str, organization: &str, application: &str) -> Self {
+		Self {
+			app_path: app_path(qualifier, organization, application),
+		}
+	}
+
+	/// Returns the platform-specific path that is appended to each base directory.
+	///
+	/// This is empty on Android, where the sandbox is already app-specific.
+	pub fn app_path(&self) -> &Path {
+		&self.app_path
+	}
+
+	/// Returns the application's cache directory, under [`cache_dir()`](crate::cache_dir).
+	pub fn cache_dir(&self) -> Option<PathBuf> {
+		self.scoped(crate::cache_dir(), "cache")
+	}
+
+	/// Returns the application's config directory, under [`config_dir()`](crate::config_dir).
+	pub fn config_dir(&self) -> Option<PathBuf> {
+		self.scoped(crate::config_dir(), "config")
+	}
+
+	/// Returns the application's local config directory, under
+	/// [`config_local_dir()`](crate::config_local_dir).
+	pub fn config_local_dir(&self) -> Option<PathBuf> {
+		self.scoped(crate::config_local_dir(), "config")
+	}
+
+	/// Returns the application's data directory, under [`data_dir()`](crate::data_dir).
+	pub fn data_dir(&self) -> Option<PathBuf> {
+		self.scoped(crate::data_dir(), "data")
+	}
+
+	/// Returns the application's local data directory, under
+	/// [`data_local_dir()`](crate::data_local_dir).
+	pub fn data_local_dir(&self) -> Option<PathBuf> {
+		self.scoped(crate::data_local_dir(), "data")
+	}
+
+	/// Returns the application's preference directory, under
+	/// [`preference_dir()`](crate::preference_dir).
+	pub fn preference_dir(&self) -> Option<PathBuf> {
+		self.scoped(crate::preference_dir(), "config")
+	}
+
+	/// Returns the application's runtime directory, under [`runtime_dir()`](crate::runtime_dir).
+	pub fn runtime_dir(&self) -> Option<PathBuf> {
+		self.scoped(crate::runtime_dir(), "runtime")
+	}
+
+	/// Returns the application's runtime directory, under
+	/// [`runtime_dir_or_fallback()`](crate::runtime_dir_or_fallback).
+	///
+	/// Only the base directory is created; the application's directory inside it
+	/// is private to the user, so [`ensure()`](crate::PathExt::ensure) is enough.
+	pub fn runtime_dir_or_fallback(&self) -> io::Result<RuntimeDir> {
+		let base = crate::runtime_dir_or_fallback()?;
+		Ok(base.map(|path| self.scope(path, "runtime")))
+	}
+
+	/// Returns the application's state directory, under [`state_dir()`](crate::state_dir).
+	pub fn state_dir(&self) -> Option<PathBuf> {
+		self.scoped(crate::state_dir(), "state")
+	}
+
+	fn scoped(&self, base: Option<PathBuf>, kind: &str) -> Option<PathBuf> {
+		base.map(|base| self.scope(base, kind))
+	}
+
+	fn scope(&self, base: PathBuf, kind: &str) -> PathBuf {
+		if self.app_path.as_os_str().is_empty() {
+			return base;
+		}
+
+		if cfg!(target_os = "windows") {
+			base.join(&self.app_path).join(kind)
+		} else {
+			base.join(&self.app_path)
+		}
+	}
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	#[cfg(any(target_os = "linux", target_os = "freebsd"))]
+	fn test_app_path_xdg_lowercase_no_spaces() {
+		let app = AppDirs::new("com", "Foo Corp", "Bar App");
+		assert_eq!(app.app_path(), Path::new("barapp"));
+	}
+
+	#[test]
+	#[cfg(target_os = "macos")]
+	fn test_app_path_apple_bundle_id() {
+		let app = AppDirs::new("com", "Foo Corp", "Bar App");
+		assert_eq!(app.app_path(), Path::new("com.Foo-Corp.Bar-App"));
+
+		let app = AppDirs::new("", "", "Bar App");
+		assert_eq!(app.app_path(), Path::new("Bar-App"));
+	}
+
+	#[test]
+	#[cfg(target_os = "windows")]
+	fn test_app_path_windows_org_and_app() {
+		let app = AppDirs::new("com", "Foo Corp", "Bar App");
+		assert_eq!(app.app_path(), Path::new("Foo Corp\\Bar App"));
+
+		let app = AppDirs::new("com", "", "Bar App");
+		assert_eq!(app.app_path(), Path::new("Bar App"));
+	}
+
+	#[test]
+	#[cfg(target_os = "windows")]
+	fn test_windows_kind_subdirs() {
+		let app = AppDirs::new("com", "Foo Corp", "Bar App");
+		let base = PathBuf::from("C:\\Users\\Alice\\AppData\\Local");
+		assert_eq!(
+			app.scoped(Some(base), "cache"),
+			Some(PathBuf::from(
+				"C:\\Users\\Alice\\AppData\\Local\\Foo Corp\\Bar App\\cache"
+			))
+		);
+	}
+
+	#[test]
+	#[cfg(target_os = "android")]
+	fn test_android_uses_base_dirs() {
+		let app = AppDirs::new("com", "Foo Corp", "Bar App");
+		assert!(app.app_path().as_os_str().is_empty());
+		assert_eq!(
+			app.scoped(Some(PathBuf::from("/data/data/com.example/files")), "data"),
+			Some(PathBuf::from("/data/data/com.example/files"))
+		);
+	}
+
+	#[test]
+	#[cfg(target_os = "linux")]
+	fn test_dirs_scoped_under_base_dirs() {
+		let app = AppDirs::new("org", "Example", "My App");
+		assert_eq!(app.cache_dir(), crate::cache_dir().map(|d| d.join("myapp")));
+		assert_eq!(
+			app.config_dir(),
+			crate::config_dir().map(|d| d.join("myapp"))
+		);
+		assert_eq!(app.data_dir(), crate::data_dir().map(|d| d.join("myapp")));
+		assert_eq!(app.state_dir(), crate::state_dir().map(|d| d.join("myapp")));
+	}
+
+	#[test]
+	fn test_scoped_none_base() {
+		let app = AppDirs::new("com", "Foo Corp", "Bar App");
+		assert_eq!(app.scoped(None, "cache"), None);
+	}
+}
@@ -1,14 +1,15 @@
 //! Apple platform implementation (macOS, iOS, tvOS, watchOS, visionOS)
 //!
-//! Uses the sysdir FFI for proper sandbox-aware directory lookups.
+//! Uses the sysdir FFI for proper sandbox-aware directory lookups. When emulated on
+//! another host, the same lookups are answered from a table of sysdir's results.
 
-use crate::SearchPathDomain;
+use crate::emulate::Platform;
+use crate::error::unsupported;
+use crate::explain::{Found, Source};
+use crate::{DirError, Resolver, SearchPathDomain};
 use std::cell::Cell;
-use std::ffi::CStr;
 use std::path::PathBuf;
 
-const PATH_MAX: usize = 1024;
-
 #[repr(u32)]
 #[derive(Debug, Copy, Clone)]
 enum SysdirDirectory {
@@ -29,22 +30,124 @@ const SYSDIR_DOMAIN_MASK_LOCAL: u32 = 2;
 const SYSDIR_DOMAIN_MASK_NETWORK: u32 = 4;
 const SYSDIR_DOMAIN_MASK_SYSTEM: u32 = 8;
 
-#[repr(transparent)]
-struct SysdirState(u32);
+// =============================================================================
+// Native sysdir (Apple hosts only)
+// =============================================================================
+
+#[cfg(any(
+	target_os = "macos",
+	target_os = "ios",
+	target_os = "tvos",
+	target_os = "watchos",
+	target_os = "visionos"
+))]
+mod native {
+	use super::SysdirDirectory;
+	use std::ffi::CStr;
+
+	const PATH_MAX: usize = 1024;
+
+	#[repr(transparent)]
+	struct SysdirState(u32);
+
+	impl SysdirState {
+		fn is_finished(&self) -> bool {
+			self.0 == 0
+		}
+	}
+
+	unsafe extern "C" {
+		fn sysdir_start_search_path_enumeration(
+			dir: SysdirDirectory,
+			domain_mask: u32,
+		) -> SysdirState;
+
+		fn sysdir_get_next_search_path_enumeration(
+			state: SysdirState,
+			path: *mut std::ffi::c_char,
+		) -> SysdirState;
+	}
+
+	/// Enumerate the raw paths sysdir reports, in order.
+	pub fn search_paths(dir: SysdirDirectory, domain_mask: u32) -> Option<Vec<String>> {
+		let mut paths = Vec::new();
+		let mut path_buf = [0i8; PATH_MAX];
+
+		unsafe {
+			let mut state = sysdir_start_search_path_enumeration(dir, domain_mask);
+			loop {
+				state = sysdir_get_next_search_path_enumeration(state, path_buf.as_mut_ptr());
+				if state.is_finished() {
+					break;
+				}
+
+				let c_str = CStr::from_ptr(path_buf.as_ptr());
+				if let Ok(path) = c_str.to_str() {
+					paths.push(path.to_string());
+				}
+			}
+		}
 
-impl SysdirState {
-	fn is_finished(&self) -> bool {
-		self.0 == 0
+		Some(paths)
 	}
 }
 
-unsafe extern "C" {
-	fn sysdir_start_search_path_enumeration(dir: SysdirDirectory, domain_mask: u32) -> SysdirState;
+#[cfg(not(any(
+	target_os = "macos",
+	target_os = "ios",
+	target_os = "tvos",
+	target_os = "watchos",
+	target_os = "visionos"
+)))]
+mod native {
+	use super::SysdirDirectory;
+
+	/// sysdir isn't available, so every lookup is emulated.
+	pub fn search_paths(_dir: SysdirDirectory, _domain_mask: u32) -> Option<Vec<String>> {
+		None
+	}
+}
+
+// =============================================================================
+// Emulated sysdir
+// =============================================================================
+
+/// The raw paths sysdir reports for a directory type in each domain of `domain_mask`,
+/// in enumeration order (user, local, network, system).
+/// This is the testable core - no FFI.
+fn emulated_search_paths(dir: SysdirDirectory, domain_mask: u32) -> Vec<String> {
+	const ALL: u32 = SYSDIR_DOMAIN_MASK_USER
+		| SYSDIR_DOMAIN_MASK_LOCAL
+		| SYSDIR_DOMAIN_MASK_NETWORK
+		| SYSDIR_DOMAIN_MASK_SYSTEM;
+
+	// Only the Library directories exist outside the user domain
+	let (leaf, domains) = match dir {
+		SysdirDirectory::Library => ("Library", ALL),
+		SysdirDirectory::Caches => ("Library/Caches", ALL & !SYSDIR_DOMAIN_MASK_NETWORK),
+		SysdirDirectory::ApplicationSupport => (
+			"Library/Application Support",
+			ALL & !SYSDIR_DOMAIN_MASK_SYSTEM,
+		),
+		SysdirDirectory::Document => ("Documents", SYSDIR_DOMAIN_MASK_USER),
+		SysdirDirectory::Desktop => ("Desktop", SYSDIR_DOMAIN_MASK_USER),
+		SysdirDirectory::Downloads => ("Downloads", SYSDIR_DOMAIN_MASK_USER),
+		SysdirDirectory::Movies => ("Movies", SYSDIR_DOMAIN_MASK_USER),
+		SysdirDirectory::Music => ("Music", SYSDIR_DOMAIN_MASK_USER),
+		SysdirDirectory::Pictures => ("Pictures", SYSDIR_DOMAIN_MASK_USER),
+		SysdirDirectory::SharedPublic => ("Public", SYSDIR_DOMAIN_MASK_USER),
+	};
 
-	fn sysdir_get_next_search_path_enumeration(
-		state: SysdirState,
-		path: *mut std::ffi::c_char,
-	) -> SysdirState;
+	[
+		(SYSDIR_DOMAIN_MASK_USER, "~/"),
+		(SYSDIR_DOMAIN_MASK_LOCAL, "/"),
+		(SYSDIR_DOMAIN_MASK_NETWORK, "/Network/"),
+		(SYSDIR_DOMAIN_MASK_SYSTEM, "/System/"),
+	]
+	.iter()
+	.filter(|(mask, _)| domain_mask & domains & mask != 0)
+	.map(|(_, prefix)| format!("{prefix}{leaf}"))
+	.collect()
 }
 
 // =============================================================================
@@ -52,198 +155,322 @@ unsafe extern "C" {
 // =============================================================================
 
 thread_local! {
-	static CURRENT_DOMAIN: Cell<u32> = const { Cell::new(SYSDIR_DOMAIN_MASK_USER) };
+	static CURRENT_DOMAIN: Cell<SearchPathDomain> = const { Cell::new(SearchPathDomain::User) };
 }
 
 pub fn set_domain(domain: SearchPathDomain) {
-	let mask = match domain {
+	CURRENT_DOMAIN.set(domain);
+}
+
+fn get_domain() -> SearchPathDomain {
+	CURRENT_DOMAIN.get()
+}
+
+fn domain_mask(domain: SearchPathDomain) -> u32 {
+	match domain {
 		SearchPathDomain::User => SYSDIR_DOMAIN_MASK_USER,
 		SearchPathDomain::Local => SYSDIR_DOMAIN_MASK_LOCAL,
 		SearchPathDomain::Network => SYSDIR_DOMAIN_MASK_NETWORK,
 		SearchPathDomain::System => SYSDIR_DOMAIN_MASK_SYSTEM,
-	};
-	CURRENT_DOMAIN.set(mask);
-}
-
-fn get_domain_mask() -> u32 {
-	CURRENT_DOMAIN.get()
+	}
 }
 
 // =============================================================================
 // Core sysdir lookup
 // =============================================================================
 
+/// Get the raw sysdir paths, from sysdir itself when running natively.
+fn search_paths(r: &Resolver, dir: SysdirDirectory, domain_mask: u32) -> Vec<String> {
+	let native = if r.platform() == Platform::current() {
+		native::search_paths(dir, domain_mask)
+	} else {
+		None
+	};
+	native.unwrap_or_else(|| emulated_search_paths(dir, domain_mask))
+}
+
 /// Get the first path for a directory type in the current domain.
-fn sysdir_path(dir: SysdirDirectory) -> Option<PathBuf> {
-	let mut path_buf = [0i8; PATH_MAX];
-	let domain = get_domain_mask();
-
-	unsafe {
-		let state = sysdir_start_search_path_enumeration(dir, domain);
-		if state.is_finished() {
-			return None;
+fn sysdir_path(r: &Resolver, dir: SysdirDirectory) -> Result<Found, DirError> {
+	let domain = get_domain();
+	match search_paths(r, dir, domain_mask(domain)).into_iter().next() {
+		Some(path) => {
+			expand_tilde(r, &path).map(|path| Found::new(path, Source::Sysdir { domain }))
 		}
+		None => unsupported(r),
+	}
+}
 
-		let state = sysdir_get_next_search_path_enumeration(state, path_buf.as_mut_ptr());
-		if state.is_finished() && path_buf[0] == 0 {
-			return None;
+/// Get every path for a directory type across the domains in `domain_mask`,
+/// in the order sysdir enumerates them.
+fn sysdir_paths(r: &Resolver, dir: SysdirDirectory, domain_mask: u32) -> Vec<PathBuf> {
+	let mut paths = Vec::new();
+	for path in search_paths(r, dir, domain_mask) {
+		if let Ok(path) = expand_tilde(r, &path)
+			&& !paths.contains(&path)
+		{
+			paths.push(path);
 		}
+	}
+	paths
+}
 
-		let c_str = CStr::from_ptr(path_buf.as_ptr());
-		let path_str = c_str.to_str().ok()?;
-
-		// Handle ~ expansion for user domain
-		if let Some(rest) = path_str.strip_prefix("~/") {
-			let home = std::env::var_os("HOME")?;
-			Some(PathBuf::from(home).join(rest))
-		} else if path_str == "~" {
-			std::env::var_os("HOME").map(PathBuf::from)
-		} else {
-			Some(PathBuf::from(path_str))
-		}
+/// Handle ~ expansion for user domain paths returned by sysdir.
+fn expand_tilde(r: &Resolver, path_str: &str) -> Result<PathBuf, DirError> {
+	if let Some(rest) = path_str.strip_prefix("~/") {
+		home(r).map(|h| h.path.join(rest))
+	} else if path_str == "~" {
+		home(r).map(|h| h.path)
+	} else {
+		Ok(PathBuf::from(path_str))
 	}
 }
 
+fn home(r: &Resolver) -> Result<Found, DirError> {
+	r.unix_home()
+}
+
 // =============================================================================
 // Directory implementations
 // =============================================================================
 
-pub fn home_dir() -> Option<PathBuf> {
+pub fn home_dir(r: &Resolver) -> Result<Found, DirError> {
 	// sysdir doesn't have a "home" directory type, use $HOME
-	std::env::var_os("HOME").map(PathBuf::from)
+	home(r)
 }
 
-pub fn cache_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::Caches)
+pub fn cache_dir(r: &Resolver) -> Result<Found, DirError> {
+	sysdir_path(r, SysdirDirectory::Caches)
 }
 
-pub fn config_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::ApplicationSupport)
+pub fn config_dir(r: &Resolver) -> Result<Found, DirError> {
+	sysdir_path(r, SysdirDirectory::ApplicationSupport)
 }
 
-pub fn config_local_dir() -> Option<PathBuf> {
-	config_dir()
+pub fn config_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	config_dir(r)
 }
 
-pub fn data_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::ApplicationSupport)
+pub fn data_dir(r: &Resolver) -> Result<Found, DirError> {
+	sysdir_path(r, SysdirDirectory::ApplicationSupport)
 }
 
-pub fn data_local_dir() -> Option<PathBuf> {
-	data_dir()
+pub fn data_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	data_dir(r)
 }
 
-pub fn executable_dir() -> Option<PathBuf> {
-	None
+pub fn executable_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn preference_dir() -> Option<PathBuf> {
+pub fn preference_dir(r: &Resolver) -> Result<Found, DirError> {
 	// sysdir doesn't have Preferences, derive from Library
-	library_dir().map(|l| l.join("Preferences"))
+	library_dir(r).map(|l| l.join("Preferences"))
 }
 
-pub fn runtime_dir() -> Option<PathBuf> {
-	None
+pub fn runtime_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn state_dir() -> Option<PathBuf> {
-	None
+pub fn state_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
 // User directories - only available on macOS, not iOS/tvOS/etc
-#[cfg(target_os = "macos")]
-pub fn audio_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::Music)
-}
-
-#[cfg(not(target_os = "macos"))]
-pub fn audio_dir() -> Option<PathBuf> {
-	None
+fn is_macos(r: &Resolver) -> bool {
+	r.platform() == Platform::MacOs
+}
+
+// Documents also exists on iOS, but not on tvOS, watchOS or visionOS, which
+// share Platform::Ios when running natively
+fn has_documents(r: &Resolver) -> bool {
+	match r.platform() {
+		Platform::MacOs => true,
+		Platform::Ios => !cfg!(any(
+			target_os = "tvos",
+			target_os = "watchos",
+			target_os = "visionos"
+		)),
+		_ => false,
+	}
 }
 
-#[cfg(target_os = "macos")]
-pub fn desktop_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::Desktop)
+pub fn audio_dir(r: &Resolver) -> Result<Found, DirError> {
+	if !is_macos(r) {
+		return unsupported(r);
+	}
+	sysdir_path(r, SysdirDirectory::Music)
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn desktop_dir() -> Option<PathBuf> {
-	None
+pub fn desktop_dir(r: &Resolver) -> Result<Found, DirError> {
+	if !is_macos(r) {
+		return unsupported(r);
+	}
+	sysdir_path(r, SysdirDirectory::Desktop)
 }
 
-#[cfg(any(target_os = "macos", target_os = "ios"))]
-pub fn document_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::Document)
+pub fn document_dir(r: &Resolver) -> Result<Found, DirError> {
+	if !has_documents(r) {
+		return unsupported(r);
+	}
+	sysdir_path(r, SysdirDirectory::Document)
 }
 
-#[cfg(not(any(target_os = "macos", target_os = "ios")))]
-pub fn document_dir() -> Option<PathBuf> {
-	None
+pub fn download_dir(r: &Resolver) -> Result<Found, DirError> {
+	if !is_macos(r) {
+		return unsupported(r);
+	}
+	sysdir_path(r, SysdirDirectory::Downloads)
 }
 
-#[cfg(target_os = "macos")]
-pub fn download_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::Downloads)
+pub fn font_dir(r: &Resolver) -> Result<Found, DirError> {
+	if !is_macos(r) {
+		return unsupported(r);
+	}
+	// sysdir doesn't have Fonts, derive from Library
+	library_dir(r).map(|l| l.join("Fonts"))
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn download_dir() -> Option<PathBuf> {
-	None
+pub fn picture_dir(r: &Resolver) -> Result<Found, DirError> {
+	if !is_macos(r) {
+		return unsupported(r);
+	}
+	sysdir_path(r, SysdirDirectory::Pictures)
 }
 
-#[cfg(target_os = "macos")]
-pub fn font_dir() -> Option<PathBuf> {
-	// sysdir doesn't have Fonts, derive from Library
-	library_dir().map(|l| l.join("Fonts"))
+pub fn public_dir(r: &Resolver) -> Result<Found, DirError> {
+	if !is_macos(r) {
+		return unsupported(r);
+	}
+	sysdir_path(r, SysdirDirectory::SharedPublic)
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn font_dir() -> Option<PathBuf> {
-	None
+pub fn template_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-#[cfg(target_os = "macos")]
-pub fn picture_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::Pictures)
+pub fn video_dir(r: &Resolver) -> Result<Found, DirError> {
+	if !is_macos(r) {
+		return unsupported(r);
+	}
+	sysdir_path(r, SysdirDirectory::Movies)
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn picture_dir() -> Option<PathBuf> {
-	None
-}
+// =============================================================================
+// System search paths
+// =============================================================================
 
-#[cfg(target_os = "macos")]
-pub fn public_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::SharedPublic)
+// The Local and System domains play the role of XDG's system-wide dirs
+pub fn data_dirs(r: &Resolver) -> Vec<PathBuf> {
+	sysdir_paths(
+		r,
+		SysdirDirectory::ApplicationSupport,
+		SYSDIR_DOMAIN_MASK_LOCAL | SYSDIR_DOMAIN_MASK_SYSTEM,
+	)
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn public_dir() -> Option<PathBuf> {
-	None
+pub fn config_dirs(r: &Resolver) -> Vec<PathBuf> {
+	data_dirs(r)
 }
 
-pub fn template_dir() -> Option<PathBuf> {
-	None
-}
+// =============================================================================
+// sysdirs extensions
+// =============================================================================
 
-#[cfg(target_os = "macos")]
-pub fn video_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::Movies)
+pub fn temp_dir(r: &Resolver) -> Result<Found, DirError> {
+	match r.env_path("TMPDIR") {
+		// `$TMPDIR` is the only source, so secure execution needs a default
+		Err(_) if r.ignores_env() => Ok(Found::new(PathBuf::from("/tmp"), Source::PlatformDefault)),
+		found => found,
+	}
 }
 
-#[cfg(not(target_os = "macos"))]
-pub fn video_dir() -> Option<PathBuf> {
-	None
+pub fn library_dir(r: &Resolver) -> Result<Found, DirError> {
+	sysdir_path(r, SysdirDirectory::Library)
 }
 
 // =============================================================================
-// sysdirs extensions
+// Tests
 // =============================================================================
 
-pub fn temp_dir() -> Option<PathBuf> {
-	std::env::var_os("TMPDIR").map(PathBuf::from)
-}
+#[cfg(test)]
+mod tests {
+	use super::*;
 
-pub fn library_dir() -> Option<PathBuf> {
-	sysdir_path(SysdirDirectory::Library)
+	#[cfg(unix)]
+	fn emulated(platform: Platform) -> Resolver {
+		Resolver::from_env([("HOME", "/Users/alice"), ("TMPDIR", "/var/folders/xy/T/")])
+			.with_platform(platform)
+	}
+
+	#[test]
+	fn test_emulated_user_domain() {
+		assert_eq!(
+			emulated_search_paths(SysdirDirectory::Caches, SYSDIR_DOMAIN_MASK_USER),
+			vec!["~/Library/Caches"]
+		);
+		assert_eq!(
+			emulated_search_paths(SysdirDirectory::SharedPublic, SYSDIR_DOMAIN_MASK_USER),
+			vec!["~/Public"]
+		);
+	}
+
+	#[test]
+	fn test_emulated_other_domains() {
+		let all = SYSDIR_DOMAIN_MASK_USER
+			| SYSDIR_DOMAIN_MASK_LOCAL
+			| SYSDIR_DOMAIN_MASK_NETWORK
+			| SYSDIR_DOMAIN_MASK_SYSTEM;
+		assert_eq!(
+			emulated_search_paths(SysdirDirectory::Library, all),
+			vec![
+				"~/Library",
+				"/Library",
+				"/Network/Library",
+				"/System/Library"
+			]
+		);
+		assert_eq!(
+			emulated_search_paths(SysdirDirectory::Caches, SYSDIR_DOMAIN_MASK_SYSTEM),
+			vec!["/System/Library/Caches"]
+		);
+		assert!(
+			emulated_search_paths(SysdirDirectory::Document, SYSDIR_DOMAIN_MASK_LOCAL).is_empty()
+		);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_emulated_macos() {
+		let r = emulated(Platform::MacOs);
+		assert_eq!(
+			r.cache_dir(),
+			Some(PathBuf::from("/Users/alice/Library/Caches"))
+		);
+		assert_eq!(
+			r.preference_dir(),
+			Some(PathBuf::from("/Users/alice/Library/Preferences"))
+		);
+		assert_eq!(
+			r.font_dir(),
+			Some(PathBuf::from("/Users/alice/Library/Fonts"))
+		);
+		assert_eq!(r.video_dir(), Some(PathBuf::from("/Users/alice/Movies")));
+		assert_eq!(r.temp_dir(), Some(PathBuf::from("/var/folders/xy/T/")));
+		assert_eq!(
+			r.data_dirs(),
+			vec![PathBuf::from("/Library/Application Support")]
+		);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_emulated_ios_has_no_user_dirs() {
+		let r = emulated(Platform::Ios);
+		assert_eq!(
+			r.data_dir(),
+			Some(PathBuf::from("/Users/alice/Library/Application Support"))
+		);
+		assert_eq!(r.audio_dir(), None);
+		assert_eq!(r.font_dir(), None);
+	}
 }
@@ -0,0 +1,325 @@
+//! `sysdirs` command-line tool
+//!
+//! Prints the directories the library resolves, for shell scripts and diagnostics.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use sysdirs::{AppDirs, Dir, DirError, Report};
+
+const USAGE: &str = "\
+Usage: sysdirs [OPTIONS] [DIR]
+       sysdirs [--json] doctor
+
+Prints DIR (e.g. cache, config-local, data), or every directory as a table.
+`doctor` checks every directory for problems, such as a missing home directory.
+
+Options:
+  --app QUALIFIER.ORG.APP  Print the application's directories, e.g. com.example.MyApp
+  --json                   Print JSON
+  --shell                  Print `export SYSDIRS_CACHE=...` lines for eval
+  -h, --help               Print this help
+  -V, --version            Print the version
+
+Exit status:
+  0  success
+  1  the directory is missing because of the environment, e.g. $HOME is unset,
+     or doctor found a warning or error
+  2  invalid arguments
+  3  the directory doesn't exist on this platform
+";
+
+/// The directories [`AppDirs`] scopes to an application.
+const APP_DIRS: &[Dir] = &[
+	Dir::Cache,
+	Dir::Config,
+	Dir::ConfigLocal,
+	Dir::Data,
+	Dir::DataLocal,
+	Dir::Preference,
+	Dir::Runtime,
+	Dir::State,
+];
+
+#[derive(Clone, Copy)]
+enum Format {
+	Plain,
+	Json,
+	Shell,
+}
+
+struct Args {
+	app: Option<AppDirs>,
+	format: Format,
+	dir: Option<Dir>,
+	doctor: bool,
+}
+
+enum Parsed {
+	Run(Args),
+	Exit(&'static str),
+}
+
+// =============================================================================
+// Arguments
+// =============================================================================
+
+fn parse_app(value: &str) -> Result<AppDirs, String> {
+	let parts: Vec<&str> = value.splitn(3, '.').collect();
+	match parts[..] {
+		[qualifier, organization, application] if !application.is_empty() => {
+			Ok(AppDirs::new(qualifier, organization, application))
+		}
+		_ => Err(format!(
+			"--app expects QUALIFIER.ORG.APP, e.g. com.example.MyApp, got {value:?}"
+		)),
+	}
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Parsed, String> {
+	let mut parsed = Args {
+		app: None,
+		format: Format::Plain,
+		dir: None,
+		doctor: false,
+	};
+
+	let mut args = args.into_iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-h" | "--help" => return Ok(Parsed::Exit(USAGE)),
+			"-V" | "--version" => {
+				return Ok(Parsed::Exit(concat!(
+					"sysdirs ",
+					env!("CARGO_PKG_VERSION"),
+					"\n"
+				)));
+			}
+			"--json" => parsed.format = Format::Json,
+			"--shell" => parsed.format = Format::Shell,
+			"--app" => {
+				let value = args.next().ok_or("--app needs a value")?;
+				parsed.app = Some(parse_app(&value)?);
+			}
+			_ if arg.starts_with("--app=") => {
+				parsed.app = Some(parse_app(&arg["--app=".len()..])?);
+			}
+			_ if arg.starts_with('-') => return Err(format!("unknown option {arg:?}")),
+			_ if parsed.dir.is_some() || parsed.doctor => {
+				return Err(format!("unexpected argument {arg:?}"));
+			}
+			"doctor" => parsed.doctor = true,
+			_ => parsed.dir = Some(arg.parse().map_err(|err| format!("{err}"))?),
+		}
+	}
+
+	if parsed.doctor && (parsed.app.is_some() || matches!(parsed.format, Format::Shell)) {
+		return Err("doctor only takes --json".to_string());
+	}
+	if let (Some(_), Some(dir)) = (&parsed.app, parsed.dir)
+		&& !APP_DIRS.contains(&dir)
+	{
+		return Err(format!("{dir} has no application directory"));
+	}
+	Ok(Parsed::Run(parsed))
+}
+
+// =============================================================================
+// Lookup
+// =============================================================================
+
+fn lookup(app: Option<&AppDirs>, dir: Dir) -> Result<PathBuf, DirError> {
+	let base = sysdirs::try_get(dir)?;
+	let Some(app) = app else {
+		return Ok(base);
+	};
+
+	// AppDirs only returns None when the base directory is missing
+	let path = match dir {
+		Dir::Cache => app.cache_dir(),
+		Dir::Config => app.config_dir(),
+		Dir::ConfigLocal => app.config_local_dir(),
+		Dir::Data => app.data_dir(),
+		Dir::DataLocal => app.data_local_dir(),
+		Dir::Preference => app.preference_dir(),
+		Dir::Runtime => app.runtime_dir(),
+		Dir::State => app.state_dir(),
+		_ => None,
+	};
+	Ok(path.unwrap_or(base))
+}
+
+fn exit_code(err: &DirError) -> u8 {
+	match err {
+		DirError::Unsupported { .. } => 3,
+		_ => 1,
+	}
+}
+
+// =============================================================================
+// Output
+// =============================================================================
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+	use std::os::unix::ffi::OsStrExt;
+	Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+	match path.to_string_lossy() {
+		Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+		Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+	}
+}
+
+/// A string when the path is UTF-8, otherwise its bytes, as `DirSet` serializes it.
+fn json_path(path: &Path) -> serde_json::Value {
+	match path.to_str() {
+		Some(s) => s.into(),
+		None => path_bytes(path).iter().copied().collect(),
+	}
+}
+
+fn shell_export(out: &mut Vec<u8>, dir: Dir, path: &Path) {
+	let name = dir.name().to_ascii_uppercase().replace('-', "_");
+	out.extend_from_slice(format!("export SYSDIRS_{name}='").as_bytes());
+	for &b in path_bytes(path).iter() {
+		if b == b'\'' {
+			out.extend_from_slice(b"'\\''");
+		} else {
+			out.push(b);
+		}
+	}
+	out.extend_from_slice(b"'\n");
+}
+
+fn print_one(args: &Args, dir: Dir) -> Result<Vec<u8>, DirError> {
+	let path = lookup(args.app.as_ref(), dir)?;
+	let mut out = Vec::new();
+	match args.format {
+		Format::Plain => {
+			out.extend_from_slice(&path_bytes(&path));
+			out.push(b'\n');
+		}
+		Format::Json => out.extend_from_slice(format!("{}\n", json_path(&path)).as_bytes()),
+		Format::Shell => shell_export(&mut out, dir, &path),
+	}
+	Ok(out)
+}
+
+fn print_all(args: &Args) -> Vec<u8> {
+	let dirs = if args.app.is_some() {
+		APP_DIRS
+	} else {
+		Dir::ALL
+	};
+	let results: Vec<_> = dirs
+		.iter()
+		.map(|&dir| (dir, lookup(args.app.as_ref(), dir)))
+		.collect();
+
+	let mut out = Vec::new();
+	match args.format {
+		Format::Plain => {
+			let width = dirs.iter().map(|dir| dir.name().len()).max().unwrap_or(0);
+			for (dir, result) in &results {
+				out.extend_from_slice(format!("{:width$}  ", dir.name()).as_bytes());
+				match result {
+					Ok(path) => out.extend_from_slice(&path_bytes(path)),
+					Err(err) => out.extend_from_slice(format!("({err})").as_bytes()),
+				}
+				out.push(b'\n');
+			}
+		}
+		Format::Json => {
+			let mut map = serde_json::Map::new();
+			for (dir, result) in &results {
+				let value = result.as_deref().map_or(serde_json::Value::Null, json_path);
+				map.insert(dir.name().to_string(), value);
+			}
+			if args.app.is_none() {
+				for (key, paths) in [
+					("data-dirs", sysdirs::data_dirs()),
+					("config-dirs", sysdirs::config_dirs()),
+				] {
+					let paths = paths.iter().map(|path| json_path(path)).collect();
+					map.insert(key.to_string(), paths);
+				}
+			}
+			let json = serde_json::Value::Object(map);
+			out.extend_from_slice(format!("{json:#}\n").as_bytes());
+		}
+		Format::Shell => {
+			for (dir, result) in &results {
+				if let Ok(path) = result {
+					shell_export(&mut out, *dir, path);
+				}
+			}
+		}
+	}
+	out
+}
+
+fn print_doctor(args: &Args, report: &Report) -> Vec<u8> {
+	match args.format {
+		Format::Json => {
+			let findings: Vec<_> = report
+				.findings()
+				.iter()
+				.map(|finding| {
+					serde_json::json!({
+						"dir": finding.dir().name(),
+						"path": finding.path().map(json_path),
+						"severity": finding.severity().to_string(),
+						"problem": finding.problem().to_string(),
+					})
+				})
+				.collect();
+			format!("{:#}\n", serde_json::Value::from(findings)).into_bytes()
+		}
+		_ if report.findings().is_empty() => b"no problems found\n".to_vec(),
+		_ => report.to_string().into_bytes(),
+	}
+}
+
+fn main() -> ExitCode {
+	let args = match parse_args(std::env::args().skip(1)) {
+		Ok(Parsed::Run(args)) => args,
+		Ok(Parsed::Exit(text)) => {
+			print!("{text}");
+			return ExitCode::SUCCESS;
+		}
+		Err(err) => {
+			eprintln!("sysdirs: {err}\nTry 'sysdirs --help' for more information.");
+			return ExitCode::from(2);
+		}
+	};
+
+	if args.doctor {
+		let report = sysdirs::doctor();
+		let _ = io::stdout().write_all(&print_doctor(&args, &report));
+		return if report.is_healthy() {
+			ExitCode::SUCCESS
+		} else {
+			ExitCode::from(1)
+		};
+	}
+
+	let out = match args.dir {
+		Some(dir) => match print_one(&args, dir) {
+			Ok(out) => out,
+			Err(err) => {
+				eprintln!("sysdirs: {dir}: {err}");
+				return ExitCode::from(exit_code(&err));
+			}
+		},
+		None => print_all(&args),
+	};
+
+	// A closed pipe (e.g. `sysdirs | head -1`) isn't worth reporting
+	let _ = io::stdout().write_all(&out);
+	ExitCode::SUCCESS
+}
@@ -0,0 +1,203 @@
+//! Directory kinds
+//!
+//! A `Dir` names one of the directory functions, so a directory can be chosen
+//! at runtime (from config or a CLI flag) and looked up with [`get()`](crate::get).
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A kind of directory, with one variant per directory function.
+///
+/// # Example
+///
+/// ```rust
+/// use sysdirs::Dir;
+///
+/// let dir: Dir = "cache".parse().unwrap();
+/// assert_eq!(sysdirs::get(dir), sysdirs::cache_dir());
+/// assert_eq!(dir.to_string(), "cache");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir {
+	/// [`home_dir()`](crate::home_dir)
+	Home,
+	/// [`cache_dir()`](crate::cache_dir)
+	Cache,
+	/// [`config_dir()`](crate::config_dir)
+	Config,
+	/// [`config_local_dir()`](crate::config_local_dir)
+	ConfigLocal,
+	/// [`data_dir()`](crate::data_dir)
+	Data,
+	/// [`data_local_dir()`](crate::data_local_dir)
+	DataLocal,
+	/// [`executable_dir()`](crate::executable_dir)
+	Executable,
+	/// [`preference_dir()`](crate::preference_dir)
+	Preference,
+	/// [`runtime_dir()`](crate::runtime_dir)
+	Runtime,
+	/// [`state_dir()`](crate::state_dir)
+	State,
+	/// [`audio_dir()`](crate::audio_dir)
+	Audio,
+	/// [`desktop_dir()`](crate::desktop_dir)
+	Desktop,
+	/// [`document_dir()`](crate::document_dir)
+	Document,
+	/// [`download_dir()`](crate::download_dir)
+	Download,
+	/// [`font_dir()`](crate::font_dir)
+	Font,
+	/// [`picture_dir()`](crate::picture_dir)
+	Picture,
+	/// [`public_dir()`](crate::public_dir)
+	Public,
+	/// [`template_dir()`](crate::template_dir)
+	Template,
+	/// [`video_dir()`](crate::video_dir)
+	Video,
+	/// [`temp_dir()`](crate::temp_dir)
+	Temp,
+	/// [`library_dir()`](crate::library_dir)
+	Library,
+}
+
+impl Dir {
+	/// Every directory kind, in the same order as the directory functions are documented.
+	pub const ALL: &'static [Dir] = &[
+		Dir::Home,
+		Dir::Cache,
+		Dir::Config,
+		Dir::ConfigLocal,
+		Dir::Data,
+		Dir::DataLocal,
+		Dir::Executable,
+		Dir::Preference,
+		Dir::Runtime,
+		Dir::State,
+		Dir::Audio,
+		Dir::Desktop,
+		Dir::Document,
+		Dir::Download,
+		Dir::Font,
+		Dir::Picture,
+		Dir::Public,
+		Dir::Template,
+		Dir::Video,
+		Dir::Temp,
+		Dir::Library,
+	];
+
+	/// Returns the short name of this directory kind, e.g. `"config-local"`.
+	///
+	/// This is the name used by [`Display`](fmt::Display) and accepted by [`FromStr`].
+	pub fn name(self) -> &'static str {
+		match self {
+			Dir::Home => "home",
+			Dir::Cache => "cache",
+			Dir::Config => "config",
+			Dir::ConfigLocal => "config-local",
+			Dir::Data => "data",
+			Dir::DataLocal => "data-local",
+			Dir::Executable => "executable",
+			Dir::Preference => "preference",
+			Dir::Runtime => "runtime",
+			Dir::State => "state",
+			Dir::Audio => "audio",
+			Dir::Desktop => "desktop",
+			Dir::Document => "document",
+			Dir::Download => "download",
+			Dir::Font => "font",
+			Dir::Picture => "picture",
+			Dir::Public => "public",
+			Dir::Template => "template",
+			Dir::Video => "video",
+			Dir::Temp => "temp",
+			Dir::Library => "library",
+		}
+	}
+}
+
+impl fmt::Display for Dir {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for Dir {
+	type Err = ParseDirError;
+
+	/// Parses a directory name such as `"cache"` or `"config-local"`.
+	///
+	/// Matching is case-insensitive, `_` may be used in place of `-`, and the function
+	/// names are accepted too, so `"config_local_dir"` also parses as [`Dir::ConfigLocal`].
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let normalized = s.trim().to_ascii_lowercase().replace('_', "-");
+		let name = normalized.strip_suffix("-dir").unwrap_or(&normalized);
+
+		Dir::ALL
+			.iter()
+			.copied()
+			.find(|dir| dir.name() == name)
+			.ok_or_else(|| ParseDirError {
+				input: s.to_string(),
+			})
+	}
+}
+
+/// Error returned when parsing an unknown directory name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDirError {
+	input: String,
+}
+
+impl fmt::Display for ParseDirError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "unknown directory kind: {:?}", self.input)
+	}
+}
+
+impl std::error::Error for ParseDirError {}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_all_is_complete_and_unique() {
+		let mut sorted = Dir::ALL.to_vec();
+		sorted.sort();
+		sorted.dedup();
+		assert_eq!(sorted.len(), Dir::ALL.len());
+		assert_eq!(Dir::ALL.len(), 21);
+	}
+
+	#[test]
+	fn test_name_round_trip() {
+		for &dir in Dir::ALL {
+			assert_eq!(dir.to_string().parse::<Dir>(), Ok(dir));
+		}
+	}
+
+	#[test]
+	fn test_parse_variants() {
+		assert_eq!("config-local".parse(), Ok(Dir::ConfigLocal));
+		assert_eq!("config_local".parse(), Ok(Dir::ConfigLocal));
+		assert_eq!("config_local_dir".parse(), Ok(Dir::ConfigLocal));
+		assert_eq!("CACHE".parse(), Ok(Dir::Cache));
+		assert_eq!(" temp ".parse(), Ok(Dir::Temp));
+	}
+
+	#[test]
+	fn test_parse_unknown() {
+		let err = "nope".parse::<Dir>().unwrap_err();
+		assert_eq!(err.to_string(), "unknown directory kind: \"nope\"");
+		assert!("".parse::<Dir>().is_err());
+		assert!("dir".parse::<Dir>().is_err());
+	}
+}
@@ -0,0 +1,246 @@
+//! The result of every directory function in one struct
+//!
+//! With the `serde` feature, a [`DirSet`] can be embedded in diagnostics bundles or
+//! IPC messages. Paths are written as strings, or as raw bytes when they aren't
+//! valid UTF-8, so non-UTF-8 paths round-trip exactly on Unix.
+
+use crate::{Dir, Resolver, Snapshot};
+use std::path::PathBuf;
+
+macro_rules! dir_set {
+	($($(#[$doc:meta])* $field:ident => $dir:ident,)*) => {
+		/// The result of every public directory function.
+		///
+		/// Each field is named after the function it holds the result of.
+		///
+		/// # Example
+		///
+		/// ```rust
+		/// let dirs = sysdirs::DirSet::capture();
+		/// assert_eq!(dirs.cache_dir, sysdirs::cache_dir());
+		/// ```
+		#[derive(Debug, Clone, PartialEq, Eq, Default)]
+		#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+		#[non_exhaustive]
+		pub struct DirSet {
+			$(
+				$(#[$doc])*
+				#[cfg_attr(feature = "serde", serde(default, with = "os_path::option"))]
+				pub $field: Option<PathBuf>,
+			)*
+			/// [`data_dirs()`](crate::data_dirs)
+			#[cfg_attr(feature = "serde", serde(default, with = "os_path::vec"))]
+			pub data_dirs: Vec<PathBuf>,
+			/// [`config_dirs()`](crate::config_dirs)
+			#[cfg_attr(feature = "serde", serde(default, with = "os_path::vec"))]
+			pub config_dirs: Vec<PathBuf>,
+		}
+
+		impl DirSet {
+			/// Resolves every directory with `resolver`.
+			pub fn from_resolver(resolver: &Resolver) -> Self {
+				Self {
+					$($field: resolver.get(Dir::$dir),)*
+					data_dirs: resolver.data_dirs(),
+					config_dirs: resolver.config_dirs(),
+				}
+			}
+		}
+
+		impl From<&Snapshot> for DirSet {
+			fn from(snapshot: &Snapshot) -> Self {
+				Self {
+					$($field: snapshot.get(Dir::$dir).map(PathBuf::from),)*
+					data_dirs: snapshot.data_dirs().to_vec(),
+					config_dirs: snapshot.config_dirs().to_vec(),
+				}
+			}
+		}
+	};
+}
+
+dir_set! {
+	/// [`home_dir()`](crate::home_dir)
+	home_dir => Home,
+	/// [`cache_dir()`](crate::cache_dir)
+	cache_dir => Cache,
+	/// [`config_dir()`](crate::config_dir)
+	config_dir => Config,
+	/// [`config_local_dir()`](crate::config_local_dir)
+	config_local_dir => ConfigLocal,
+	/// [`data_dir()`](crate::data_dir)
+	data_dir => Data,
+	/// [`data_local_dir()`](crate::data_local_dir)
+	data_local_dir => DataLocal,
+	/// [`executable_dir()`](crate::executable_dir)
+	executable_dir => Executable,
+	/// [`preference_dir()`](crate::preference_dir)
+	preference_dir => Preference,
+	/// [`runtime_dir()`](crate::runtime_dir)
+	runtime_dir => Runtime,
+	/// [`state_dir()`](crate::state_dir)
+	state_dir => State,
+	/// [`audio_dir()`](crate::audio_dir)
+	audio_dir => Audio,
+	/// [`desktop_dir()`](crate::desktop_dir)
+	desktop_dir => Desktop,
+	/// [`document_dir()`](crate::document_dir)
+	document_dir => Document,
+	/// [`download_dir()`](crate::download_dir)
+	download_dir => Download,
+	/// [`font_dir()`](crate::font_dir)
+	font_dir => Font,
+	/// [`picture_dir()`](crate::picture_dir)
+	picture_dir => Picture,
+	/// [`public_dir()`](crate::public_dir)
+	public_dir => Public,
+	/// [`template_dir()`](crate::template_dir)
+	template_dir => Template,
+	/// [`video_dir()`](crate::video_dir)
+	video_dir => Video,
+	/// [`temp_dir()`](crate::temp_dir)
+	temp_dir => Temp,
+	/// [`library_dir()`](crate::library_dir)
+	library_dir => Library,
+}
+
+impl DirSet {
+	/// Resolves every directory from the process environment, as the free functions would.
+	pub fn capture() -> Self {
+		Self::from_resolver(&Resolver::from_process_env())
+	}
+}
+
+// =============================================================================
+// Path encoding
+// =============================================================================
+
+/// Serde helpers that keep non-UTF-8 paths intact.
+///
+/// Human-readable formats get a string when the path is valid UTF-8 and its bytes
+/// otherwise. Binary formats always get the bytes, so they needn't be self-describing.
+/// Bytes are the raw `OsStr` on Unix and a lossy UTF-8 conversion elsewhere.
+#[cfg(feature = "serde")]
+mod os_path {
+	use serde::de::{self, Deserializer, SeqAccess, Visitor};
+	use serde::ser::Serializer;
+	use serde::{Deserialize, Serialize};
+	use std::borrow::Cow;
+	use std::fmt;
+	use std::path::{Path, PathBuf};
+
+	#[cfg(unix)]
+	fn to_bytes(path: &Path) -> Cow<'_, [u8]> {
+		use std::os::unix::ffi::OsStrExt;
+		Cow::Borrowed(path.as_os_str().as_bytes())
+	}
+
+	#[cfg(not(unix))]
+	fn to_bytes(path: &Path) -> Cow<'_, [u8]> {
+		match path.to_string_lossy() {
+			Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+			Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+		}
+	}
+
+	#[cfg(unix)]
+	fn from_bytes(bytes: Vec<u8>) -> PathBuf {
+		use std::os::unix::ffi::OsStringExt;
+		PathBuf::from(std::ffi::OsString::from_vec(bytes))
+	}
+
+	#[cfg(not(unix))]
+	fn from_bytes(bytes: Vec<u8>) -> PathBuf {
+		PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+	}
+
+	struct Encoded<'a>(&'a Path);
+
+	impl Serialize for Encoded<'_> {
+		fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			match self.0.to_str() {
+				Some(s) if serializer.is_human_readable() => serializer.serialize_str(s),
+				_ => serializer.serialize_bytes(&to_bytes(self.0)),
+			}
+		}
+	}
+
+	struct Decoded(PathBuf);
+
+	impl<'de> Deserialize<'de> for Decoded {
+		fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+			if deserializer.is_human_readable() {
+				deserializer.deserialize_any(PathVisitor).map(Decoded)
+			} else {
+				deserializer.deserialize_byte_buf(PathVisitor).map(Decoded)
+			}
+		}
+	}
+
+	struct PathVisitor;
+
+	impl<'de> Visitor<'de> for PathVisitor {
+		type Value = PathBuf;
+
+		fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+			f.write_str("a path as a string or bytes")
+		}
+
+		fn visit_str<E: de::Error>(self, value: &str) -> Result<PathBuf, E> {
+			Ok(PathBuf::from(value))
+		}
+
+		fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<PathBuf, E> {
+			Ok(from_bytes(value.to_vec()))
+		}
+
+		fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<PathBuf, E> {
+			Ok(from_bytes(value))
+		}
+
+		// Formats without a bytes type, like JSON, write bytes as a sequence
+		fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<PathBuf, A::Error> {
+			let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+			while let Some(b) = seq.next_element()? {
+				bytes.push(b);
+			}
+			Ok(from_bytes(bytes))
+		}
+	}
+
+	pub mod option {
+		use super::*;
+
+		pub fn serialize<S: Serializer>(
+			path: &Option<PathBuf>,
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			path.as_deref().map(Encoded).serialize(serializer)
+		}
+
+		pub fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<Option<PathBuf>, D::Error> {
+			let path: Option<Decoded> = Deserialize::deserialize(deserializer)?;
+			Ok(path.map(|Decoded(path)| path))
+		}
+	}
+
+	pub mod vec {
+		use super::*;
+
+		pub fn serialize<S: Serializer>(
+			paths: &[PathBuf],
+			serializer: S,
+		) -> Result<S::Ok, S::Error> {
+			serializer.collect_seq(paths.iter().map(|path| Encoded(path)))
+		}
+
+		pub fn deserialize<'de, D: Deserializer<'de>>(
+			deserializer: D,
+		) -> Result<Vec<PathBuf>, D::Error> {
+			let paths: Vec<Decoded> = Deserialize::deserialize(deserializer)?;
+			Ok(paths.into_iter().map(|Decoded(path)| path).collect())
+		}
+	}
+}
@@ -0,0 +1,449 @@
+//! Health checks for the resolved directories
+//!
+//! Misconfigured home directories show up as confusing bugs far from their cause.
+//! [`doctor()`] goes through every directory and reports what looks wrong.
+
+use crate::emulate::Platform;
+use crate::explain::Source;
+use crate::{Dir, DirError, Resolver, linux, passwd};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// XDG variables, and the directory each one sets.
+const XDG_VARS: &[(&str, Dir)] = &[
+	("XDG_CACHE_HOME", Dir::Cache),
+	("XDG_CONFIG_HOME", Dir::Config),
+	("XDG_DATA_HOME", Dir::Data),
+	("XDG_STATE_HOME", Dir::State),
+	("XDG_BIN_HOME", Dir::Executable),
+	("XDG_RUNTIME_DIR", Dir::Runtime),
+	("TMPDIR", Dir::Temp),
+];
+
+/// XDG user directory variables, which only the Linux backend reads.
+const XDG_USER_DIR_VARS: &[(&str, Dir)] = &[
+	("XDG_MUSIC_DIR", Dir::Audio),
+	("XDG_DESKTOP_DIR", Dir::Desktop),
+	("XDG_DOCUMENTS_DIR", Dir::Document),
+	("XDG_DOWNLOAD_DIR", Dir::Download),
+	("XDG_PICTURES_DIR", Dir::Picture),
+	("XDG_PUBLICSHARE_DIR", Dir::Public),
+	("XDG_TEMPLATES_DIR", Dir::Template),
+	("XDG_VIDEOS_DIR", Dir::Video),
+];
+
+/// How serious a [`Finding`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+	/// Expected in normal use, e.g. a directory that hasn't been created yet.
+	Info,
+	/// Works, but is probably not what was intended or is unsafe.
+	Warning,
+	/// Programs using the directory are likely to fail.
+	Error,
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Severity::Info => "info",
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+		})
+	}
+}
+
+/// Something [`doctor()`] found wrong with a directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Problem {
+	/// The directory doesn't exist.
+	Missing,
+	/// The path exists but isn't a directory.
+	NotADirectory,
+	/// The current user can't write to the directory.
+	NotWritable,
+	/// Anyone can write to the directory, and it lacks the sticky bit that makes
+	/// that safe, as `/tmp` has.
+	WorldWritable {
+		/// The permission bits, e.g. `0o777`.
+		mode: u32,
+	},
+	/// The directory belongs to another user.
+	OwnedByOtherUser {
+		/// The owner's user id.
+		uid: u32,
+	},
+	/// `$XDG_RUNTIME_DIR` isn't mode `0700`, as the XDG spec requires.
+	RuntimeDirMode {
+		/// The permission bits, e.g. `0o755`.
+		mode: u32,
+	},
+	/// An environment variable was ignored because its value isn't a usable path.
+	InvalidEnvVar {
+		/// The variable's name.
+		var: String,
+		/// The rejected value.
+		value: OsString,
+	},
+	/// The directory couldn't be resolved.
+	Unresolved(DirError),
+}
+
+impl fmt::Display for Problem {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Problem::Missing => f.write_str("does not exist"),
+			Problem::NotADirectory => f.write_str("is not a directory"),
+			Problem::NotWritable => f.write_str("is not writable by the current user"),
+			Problem::WorldWritable { mode } => write!(f, "is world-writable (mode {mode:o})"),
+			Problem::OwnedByOtherUser { uid } => write!(f, "is owned by another user (uid {uid})"),
+			Problem::RuntimeDirMode { mode } => {
+				write!(f, "has mode {mode:o}, but XDG_RUNTIME_DIR must be 700")
+			}
+			Problem::InvalidEnvVar { var, value } => {
+				write!(
+					f,
+					"{var} is set to {value:?}, which is not a usable path, so it was ignored"
+				)
+			}
+			Problem::Unresolved(err) => write!(f, "could not be resolved: {err}"),
+		}
+	}
+}
+
+/// A problem with one directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+	dir: Dir,
+	path: Option<PathBuf>,
+	problem: Problem,
+}
+
+impl Finding {
+	/// Returns the directory kind the problem is with.
+	pub fn dir(&self) -> Dir {
+		self.dir
+	}
+
+	/// Returns the path that was checked, if the directory resolved.
+	pub fn path(&self) -> Option<&Path> {
+		self.path.as_deref()
+	}
+
+	/// Returns what is wrong.
+	pub fn problem(&self) -> &Problem {
+		&self.problem
+	}
+
+	/// Returns how serious the problem is.
+	pub fn severity(&self) -> Severity {
+		match &self.problem {
+			// A missing home breaks everything derived from it; other dirs are created on demand
+			Problem::Missing if self.dir == Dir::Home => Severity::Error,
+			Problem::Missing => Severity::Info,
+			Problem::NotADirectory | Problem::NotWritable => Severity::Error,
+			Problem::WorldWritable { .. }
+			| Problem::OwnedByOtherUser { .. }
+			| Problem::RuntimeDirMode { .. }
+			| Problem::InvalidEnvVar { .. } => Severity::Warning,
+			Problem::Unresolved(DirError::HomeNotSet) => Severity::Error,
+			Problem::Unresolved(_) => Severity::Info,
+		}
+	}
+}
+
+impl fmt::Display for Finding {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}: ", self.severity(), self.dir)?;
+		if let Some(path) = &self.path {
+			write!(f, "{} ", path.display())?;
+		}
+		write!(f, "{}", self.problem)
+	}
+}
+
+/// The findings of a [`doctor()`] run, most serious first.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+	findings: Vec<Finding>,
+}
+
+impl Report {
+	/// Returns every finding, most serious first.
+	pub fn findings(&self) -> &[Finding] {
+		&self.findings
+	}
+
+	/// Returns the severity of the most serious finding, if there are any.
+	pub fn worst(&self) -> Option<Severity> {
+		self.findings.first().map(Finding::severity)
+	}
+
+	/// Returns `true` if nothing worse than [`Severity::Info`] was found.
+	pub fn is_healthy(&self) -> bool {
+		self.worst().is_none_or(|worst| worst == Severity::Info)
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for finding in &self.findings {
+			writeln!(f, "{finding}")?;
+		}
+		Ok(())
+	}
+}
+
+// =============================================================================
+// Core logic (testable, no filesystem access)
+// =============================================================================
+
+/// What the filesystem says about a directory.
+struct Stat {
+	is_dir: bool,
+	writable: bool,
+	/// Permission bits and owner, where the platform has them.
+	unix: Option<(u32, u32)>,
+}
+
+/// Check one directory's metadata, given the current user's id.
+/// This is the testable core - no filesystem access.
+fn check_stat(dir: Dir, stat: Option<&Stat>, euid: Option<u32>) -> Vec<Problem> {
+	let Some(stat) = stat else {
+		return vec![Problem::Missing];
+	};
+	if !stat.is_dir {
+		return vec![Problem::NotADirectory];
+	}
+
+	let mut problems = Vec::new();
+	if !stat.writable {
+		problems.push(Problem::NotWritable);
+	}
+
+	if let Some((mode, uid)) = stat.unix {
+		const STICKY: u32 = 0o1000;
+		let perms = mode & 0o777;
+
+		// Shared directories like /tmp are world-writable and owned by root by design
+		let shared = mode & STICKY != 0;
+		if !shared && perms & 0o002 != 0 {
+			problems.push(Problem::WorldWritable { mode: perms });
+		}
+		if !shared
+			&& let Some(euid) = euid
+			&& uid != euid
+		{
+			problems.push(Problem::OwnedByOtherUser { uid });
+		}
+		if dir == Dir::Runtime && perms != 0o700 {
+			problems.push(Problem::RuntimeDirMode { mode: perms });
+		}
+	}
+	problems
+}
+
+// =============================================================================
+// Filesystem access
+// =============================================================================
+
+#[cfg(unix)]
+mod sys {
+	use std::ffi::{CString, c_char, c_int};
+	use std::os::unix::ffi::OsStrExt;
+	use std::path::Path;
+
+	const W_OK: c_int = 2;
+
+	unsafe extern "C" {
+		fn access(path: *const c_char, mode: c_int) -> c_int;
+	}
+
+	pub fn writable(path: &Path, _meta: &std::fs::Metadata) -> bool {
+		let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+			return false;
+		};
+		// SAFETY: the path is NUL-terminated
+		unsafe { access(path.as_ptr(), W_OK) == 0 }
+	}
+
+	pub fn mode_and_owner(meta: &std::fs::Metadata) -> Option<(u32, u32)> {
+		use std::os::unix::fs::MetadataExt;
+		Some((meta.mode(), meta.uid()))
+	}
+}
+
+#[cfg(not(unix))]
+mod sys {
+	use std::path::Path;
+
+	pub fn writable(_path: &Path, meta: &std::fs::Metadata) -> bool {
+		!meta.permissions().readonly()
+	}
+
+	pub fn mode_and_owner(_meta: &std::fs::Metadata) -> Option<(u32, u32)> {
+		None
+	}
+}
+
+fn stat(path: &Path) -> Option<Stat> {
+	let meta = std::fs::metadata(path).ok()?;
+	Some(Stat {
+		is_dir: meta.is_dir(),
+		writable: sys::writable(path, &meta),
+		unix: sys::mode_and_owner(&meta),
+	})
+}
+
+/// XDG variables that are set, but to something the XDG backends reject.
+fn invalid_env_vars(r: &Resolver) -> Vec<(Dir, String, OsString)> {
+	if !matches!(r.platform(), Platform::Linux | Platform::Unix) {
+		return Vec::new();
+	}
+
+	let user_dirs = match r.platform() {
+		Platform::Linux => XDG_USER_DIR_VARS,
+		_ => &[],
+	};
+	let home = r.unix_home().ok().map(|home| home.path);
+	XDG_VARS
+		.iter()
+		.chain(user_dirs)
+		.filter_map(|&(var, dir)| {
+			let value = r.var_os(var).filter(|value| !value.is_empty())?;
+			match linux::resolve_env_path(Some(&value), home.as_deref(), &|name| r.user_home(name))
+			{
+				Some(_) => None,
+				None => Some((dir, var.to_string(), value)),
+			}
+		})
+		.collect()
+}
+
+/// Checks every directory `r` resolves. See [`doctor()`](crate::doctor).
+pub(crate) fn run(r: &Resolver) -> Report {
+	let euid = passwd::euid();
+	let mut findings = Vec::new();
+
+	for (dir, var, value) in invalid_env_vars(r) {
+		findings.push(Finding {
+			dir,
+			path: None,
+			problem: Problem::InvalidEnvVar { var, value },
+		});
+	}
+
+	for &dir in Dir::ALL {
+		let resolution = r.explain(dir);
+		let Some(path) = resolution.path() else {
+			match resolution.source() {
+				// Unsupported is expected, and invalid values are reported above
+				Source::Missing(DirError::Unsupported { .. })
+				| Source::Missing(DirError::EnvVarInvalid { .. }) => {}
+				Source::Missing(err) => findings.push(Finding {
+					dir,
+					path: None,
+					problem: Problem::Unresolved(err.clone()),
+				}),
+				_ => {}
+			}
+			continue;
+		};
+
+		for problem in check_stat(dir, stat(path).as_ref(), euid) {
+			findings.push(Finding {
+				dir,
+				path: Some(path.to_path_buf()),
+				problem,
+			});
+		}
+	}
+
+	// Stable, so directories stay in Dir::ALL order within a severity
+	findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity()));
+	Report { findings }
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn unix(mode: u32, uid: u32) -> Stat {
+		Stat {
+			is_dir: true,
+			writable: true,
+			unix: Some((mode, uid)),
+		}
+	}
+
+	#[test]
+	fn test_healthy() {
+		assert_eq!(
+			check_stat(Dir::Cache, Some(&unix(0o40755, 1000)), Some(1000)),
+			vec![]
+		);
+		assert_eq!(
+			check_stat(Dir::Runtime, Some(&unix(0o40700, 1000)), Some(1000)),
+			vec![]
+		);
+	}
+
+	#[test]
+	fn test_missing_and_not_dir() {
+		assert_eq!(
+			check_stat(Dir::Home, None, Some(1000)),
+			vec![Problem::Missing]
+		);
+		let file = Stat {
+			is_dir: false,
+			..unix(0o100644, 1000)
+		};
+		assert_eq!(
+			check_stat(Dir::Cache, Some(&file), Some(1000)),
+			vec![Problem::NotADirectory]
+		);
+	}
+
+	#[test]
+	fn test_world_writable_and_owner() {
+		assert_eq!(
+			check_stat(Dir::Data, Some(&unix(0o40777, 0)), Some(1000)),
+			vec![
+				Problem::WorldWritable { mode: 0o777 },
+				Problem::OwnedByOtherUser { uid: 0 },
+			]
+		);
+
+		// /tmp is fine: the sticky bit makes sharing it safe
+		assert_eq!(
+			check_stat(Dir::Temp, Some(&unix(0o41777, 0)), Some(1000)),
+			vec![]
+		);
+	}
+
+	#[test]
+	fn test_runtime_mode() {
+		assert_eq!(
+			check_stat(Dir::Runtime, Some(&unix(0o40755, 1000)), Some(1000)),
+			vec![Problem::RuntimeDirMode { mode: 0o755 }]
+		);
+	}
+
+	#[test]
+	fn test_not_writable() {
+		let stat = Stat {
+			writable: false,
+			..unix(0o40555, 1000)
+		};
+		assert_eq!(
+			check_stat(Dir::Config, Some(&stat), Some(1000)),
+			vec![Problem::NotWritable]
+		);
+	}
+}
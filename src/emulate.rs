@@ -0,0 +1,192 @@
+//! Cross-platform path emulation
+//!
+//! Computes the directories another platform would use, e.g. what
+//! [`config_dir()`](crate::config_dir) returns on Windows, from a Linux build machine.
+//!
+//! Emulation runs the same backend code as the real platform, so the two can't drift.
+//! Pick a platform with [`Resolver::with_platform()`](crate::Resolver::with_platform)
+//! and supply the environment that platform would see:
+//!
+//! ```rust
+//! use std::path::PathBuf;
+//! use sysdirs::Resolver;
+//! use sysdirs::emulate::Platform;
+//!
+//! let windows = Resolver::from_env([
+//!     ("USERPROFILE", r"C:\Users\Alice"),
+//!     ("APPDATA", r"C:\Users\Alice\AppData\Roaming"),
+//! ])
+//! .with_platform(Platform::Windows);
+//!
+//! assert_eq!(
+//!     windows.config_dir(),
+//!     Some(PathBuf::from(r"C:\Users\Alice\AppData\Roaming"))
+//! );
+//! assert_eq!(
+//!     windows.audio_dir(),
+//!     Some(PathBuf::from(r"C:\Users\Alice\Music"))
+//! );
+//! ```
+//!
+//! What each platform reads:
+//!
+//! |Platform | Inputs                                                                      |
+//! | ------- | --------------------------------------------------------------------------- |
+//! | Linux   | `HOME`, `XDG_*` variables and `$XDG_CONFIG_HOME/user-dirs.dirs`, `TMPDIR`  |
+//! | Unix    | `HOME`, `XDG_*` variables, `TMPDIR`                                         |
+//! | macOS   | `HOME`, `TMPDIR`, and the domain from [`set_domain()`](crate::set_domain)   |
+//! | iOS     | `HOME` (the sandbox container), `TMPDIR`                                    |
+//! | Windows | `USERPROFILE`, `APPDATA`, `LOCALAPPDATA`, `ProgramData`, `PUBLIC`, `TEMP`  |
+//! | Android | [`Resolver::with_android_dirs()`](crate::Resolver::with_android_dirs)       |
+//! | WASM    | nothing, every directory is `None`                                          |
+//!
+//! Windows paths are always joined with `\`, so they come out exactly as on Windows.
+//! The Unix-style platforms join with the host's separator, which makes them exact on
+//! any Unix host.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A platform whose directory conventions can be emulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Platform {
+	/// Linux, following the XDG base and user directory specifications
+	Linux,
+	/// Other Unix systems such as FreeBSD, following the XDG base directory specification
+	Unix,
+	/// macOS
+	MacOs,
+	/// iOS, and the other mobile Apple platforms (tvOS, watchOS, visionOS) on those hosts
+	Ios,
+	/// Windows
+	Windows,
+	/// Android
+	Android,
+	/// WASM, which has no filesystem
+	Wasm,
+}
+
+impl Platform {
+	/// Every platform.
+	pub const ALL: &'static [Platform] = &[
+		Platform::Linux,
+		Platform::Unix,
+		Platform::MacOs,
+		Platform::Ios,
+		Platform::Windows,
+		Platform::Android,
+		Platform::Wasm,
+	];
+
+	/// Returns the platform this crate was compiled for.
+	pub const fn current() -> Platform {
+		if cfg!(target_os = "macos") {
+			Platform::MacOs
+		} else if cfg!(any(
+			target_os = "ios",
+			target_os = "tvos",
+			target_os = "watchos",
+			target_os = "visionos"
+		)) {
+			Platform::Ios
+		} else if cfg!(target_os = "linux") {
+			Platform::Linux
+		} else if cfg!(target_os = "windows") {
+			Platform::Windows
+		} else if cfg!(target_os = "android") {
+			Platform::Android
+		} else if cfg!(target_arch = "wasm32") {
+			Platform::Wasm
+		} else {
+			Platform::Unix
+		}
+	}
+
+	/// Returns the short name of this platform, e.g. `"macos"`.
+	///
+	/// This is the name used by [`Display`](fmt::Display) and accepted by [`FromStr`].
+	pub fn name(self) -> &'static str {
+		match self {
+			Platform::Linux => "linux",
+			Platform::Unix => "unix",
+			Platform::MacOs => "macos",
+			Platform::Ios => "ios",
+			Platform::Windows => "windows",
+			Platform::Android => "android",
+			Platform::Wasm => "wasm",
+		}
+	}
+}
+
+impl Default for Platform {
+	fn default() -> Self {
+		Platform::current()
+	}
+}
+
+impl fmt::Display for Platform {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.name())
+	}
+}
+
+impl FromStr for Platform {
+	type Err = ParsePlatformError;
+
+	/// Parses a platform name such as `"windows"` or `"macos"`. Matching is case-insensitive.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let name = s.trim().to_ascii_lowercase();
+
+		Platform::ALL
+			.iter()
+			.copied()
+			.find(|platform| platform.name() == name)
+			.ok_or_else(|| ParsePlatformError {
+				input: s.to_string(),
+			})
+	}
+}
+
+/// Error returned when parsing an unknown platform name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePlatformError {
+	input: String,
+}
+
+impl fmt::Display for ParsePlatformError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "unknown platform: {:?}", self.input)
+	}
+}
+
+impl std::error::Error for ParsePlatformError {}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_name_round_trip() {
+		for &platform in Platform::ALL {
+			assert_eq!(platform.to_string().parse::<Platform>(), Ok(platform));
+		}
+	}
+
+	#[test]
+	fn test_parse_case_insensitive() {
+		assert_eq!("MacOS".parse(), Ok(Platform::MacOs));
+		assert_eq!(" Windows ".parse(), Ok(Platform::Windows));
+		let err = "beos".parse::<Platform>().unwrap_err();
+		assert_eq!(err.to_string(), "unknown platform: \"beos\"");
+	}
+
+	#[test]
+	#[cfg(target_os = "linux")]
+	fn test_current_linux() {
+		assert_eq!(Platform::current(), Platform::Linux);
+	}
+}
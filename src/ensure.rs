@@ -0,0 +1,310 @@
+//! Creating directories with explicit permissions
+//!
+//! `create_dir_all` leaves the mode to the umask, which usually means 0755. State,
+//! runtime and credential directories should not be readable by other users.
+//!
+//! `create_dir_all` also follows symlinks, so a predictable path under `/tmp` can be
+//! redirected by another user who creates it first. [`create_secure()`] walks the
+//! path with `openat()` instead.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Creates `path` and any missing parents with `mode`, regardless of the umask.
+///
+/// Parents that already exist, such as `$HOME`, are left alone. If `path` itself
+/// already exists, it must be a directory whose permissions are no looser than `mode`.
+#[cfg(unix)]
+pub(crate) fn create_with_mode(path: &Path, mode: u32) -> io::Result<()> {
+	use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+	let missing: Vec<&Path> = path
+		.ancestors()
+		.take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+		.collect();
+
+	let mut created = false;
+	for dir in missing.iter().rev() {
+		match fs::DirBuilder::new().mode(mode).create(dir) {
+			// The umask may have cleared bits from `mode`
+			Ok(()) => {
+				fs::set_permissions(dir, fs::Permissions::from_mode(mode))?;
+				created = *dir == path;
+			}
+			// Created concurrently, so it's checked below like any existing directory
+			Err(err) if err.kind() == io::ErrorKind::AlreadyExists && dir.is_dir() => {}
+			Err(err) => return Err(err),
+		}
+	}
+	if created {
+		return Ok(());
+	}
+
+	let meta = fs::metadata(path)?;
+	if !meta.is_dir() {
+		return Err(io::Error::new(
+			io::ErrorKind::NotADirectory,
+			format!("{} exists and is not a directory", path.display()),
+		));
+	}
+	let actual = meta.permissions().mode() & 0o777;
+	if actual & !mode != 0 {
+		return Err(io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			format!(
+				"{} already exists with mode {actual:03o}, which is looser than {:03o}",
+				path.display(),
+				mode & 0o777
+			),
+		));
+	}
+	Ok(())
+}
+
+/// Unix modes don't apply, so this is `create_dir_all`.
+#[cfg(not(unix))]
+pub(crate) fn create_with_mode(path: &Path, _mode: u32) -> io::Result<()> {
+	fs::create_dir_all(path)
+}
+
+/// Like [`create_with_mode()`], but refuses symlinks and directories planted by
+/// other users.
+///
+/// The path is walked from `/` one component at a time with `openat()`, creating
+/// missing directories with `mkdirat()`. A symlink is only followed when the
+/// directory holding it can't be written by other users, so `/tmp -> private/tmp`
+/// on macOS is fine but `/tmp/app -> ~/.ssh` is not. Below a directory that other
+/// users can write to, every directory must belong to root or the current user,
+/// and the final directory must always belong to the current user.
+#[cfg(unix)]
+pub(crate) fn create_secure(path: &Path, mode: u32) -> io::Result<()> {
+	use std::ffi::CString;
+	use std::os::unix::ffi::OsStrExt;
+	use std::os::unix::fs::{MetadataExt, PermissionsExt};
+	use std::path::{Component, PathBuf};
+
+	if !path.is_absolute() {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			format!("{} is not an absolute path", path.display()),
+		));
+	}
+
+	let euid = sys::euid();
+	let mut dir = sys::open_root()?;
+	let mut walked = PathBuf::from("/");
+	let mut created = false;
+	for component in path.components() {
+		let name = match component {
+			Component::RootDir => continue,
+			Component::Normal(name) => name,
+			_ => {
+				return Err(io::Error::new(
+					io::ErrorKind::InvalidInput,
+					format!("{} contains `..`", path.display()),
+				));
+			}
+		};
+		walked.push(name);
+		let c_name = CString::new(name.as_bytes())?;
+
+		let parent_trusted = trusted(&dir.metadata()?, euid);
+		let opened = match sys::open_dir_at(&dir, &c_name, parent_trusted) {
+			Err(err) if err.kind() == io::ErrorKind::NotFound => {
+				match sys::mkdir_at(&dir, &c_name, mode) {
+					Ok(()) => created = true,
+					// Created concurrently, so it's checked like any existing directory
+					Err(err) if err.kind() == io::ErrorKind::AlreadyExists => created = false,
+					Err(err) => return Err(err),
+				}
+				sys::open_dir_at(&dir, &c_name, false)
+			}
+			opened => {
+				created = false;
+				opened
+			}
+		};
+		let child = opened.map_err(|err| refuse_symlink(&walked).unwrap_or(err))?;
+
+		let meta = child.metadata()?;
+		if !parent_trusted && meta.uid() != 0 && meta.uid() != euid {
+			return Err(owned_by_other(&walked, meta.uid()));
+		}
+		if created && meta.uid() == euid {
+			// The umask may have cleared bits from `mode`
+			child.set_permissions(fs::Permissions::from_mode(mode))?;
+		}
+		dir = child;
+	}
+
+	let meta = dir.metadata()?;
+	if meta.uid() != euid {
+		return Err(owned_by_other(path, meta.uid()));
+	}
+	let actual = meta.mode() & 0o777;
+	if !created && actual & !mode != 0 {
+		return Err(io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			format!(
+				"{} already exists with mode {actual:03o}, which is looser than {:03o}",
+				path.display(),
+				mode & 0o777
+			),
+		));
+	}
+	Ok(())
+}
+
+/// Unix permissions and symlink attacks don't apply, so this is `create_dir_all`.
+#[cfg(not(unix))]
+pub(crate) fn create_secure(path: &Path, _mode: u32) -> io::Result<()> {
+	fs::create_dir_all(path)
+}
+
+/// Whether only root or the current user could have put entries in a directory.
+#[cfg(unix)]
+fn trusted(meta: &fs::Metadata, euid: u32) -> bool {
+	use std::os::unix::fs::MetadataExt;
+	(meta.uid() == 0 || meta.uid() == euid) && meta.mode() & 0o022 == 0
+}
+
+#[cfg(unix)]
+fn refuse_symlink(path: &Path) -> Option<io::Error> {
+	let meta = fs::symlink_metadata(path).ok()?;
+	meta.file_type().is_symlink().then(|| {
+		io::Error::new(
+			io::ErrorKind::PermissionDenied,
+			format!(
+				"refusing to follow {}, a symlink in a directory other users can write to",
+				path.display()
+			),
+		)
+	})
+}
+
+#[cfg(unix)]
+fn owned_by_other(path: &Path, uid: u32) -> io::Error {
+	io::Error::new(
+		io::ErrorKind::PermissionDenied,
+		format!("{} is owned by another user (uid {uid})", path.display()),
+	)
+}
+
+#[cfg(unix)]
+pub(crate) mod sys {
+	use std::ffi::{CStr, c_char, c_int};
+	use std::fs::File;
+	use std::io;
+	use std::os::fd::{AsRawFd, FromRawFd};
+
+	#[cfg(any(
+		target_vendor = "apple",
+		target_os = "freebsd",
+		target_os = "dragonfly"
+	))]
+	type ModeT = u16;
+	#[cfg(not(any(
+		target_vendor = "apple",
+		target_os = "freebsd",
+		target_os = "dragonfly"
+	)))]
+	type ModeT = u32;
+
+	unsafe extern "C" {
+		fn getuid() -> u32;
+		fn geteuid() -> u32;
+		fn getgid() -> u32;
+		fn getegid() -> u32;
+		fn open(path: *const c_char, flags: c_int, ...) -> c_int;
+		fn openat(dirfd: c_int, path: *const c_char, flags: c_int, ...) -> c_int;
+		fn mkdirat(dirfd: c_int, path: *const c_char, mode: ModeT) -> c_int;
+	}
+
+	/// `(O_DIRECTORY, O_NOFOLLOW, O_CLOEXEC)`, which differ between targets.
+	const FLAGS: Option<(c_int, c_int, c_int)> =
+		if cfg!(any(target_os = "linux", target_os = "android")) {
+			if cfg!(any(target_arch = "sparc", target_arch = "sparc64")) {
+				None
+			} else if cfg!(any(
+				target_arch = "aarch64",
+				target_arch = "arm",
+				target_arch = "m68k",
+				target_arch = "powerpc",
+				target_arch = "powerpc64"
+			)) {
+				Some((0o40000, 0o100000, 0o2000000))
+			} else {
+				Some((0o200000, 0o400000, 0o2000000))
+			}
+		} else if cfg!(target_vendor = "apple") {
+			Some((0x100000, 0x100, 0x1000000))
+		} else if cfg!(target_os = "freebsd") {
+			Some((0x20000, 0x100, 0x100000))
+		} else if cfg!(target_os = "openbsd") {
+			Some((0x20000, 0x100, 0x10000))
+		} else if cfg!(target_os = "netbsd") {
+			Some((0x200000, 0x100, 0x400000))
+		} else {
+			None
+		};
+
+	fn flags(follow: bool) -> io::Result<c_int> {
+		let (directory, nofollow, cloexec) = FLAGS.ok_or_else(|| {
+			io::Error::new(
+				io::ErrorKind::Unsupported,
+				"symlink-safe directory creation is not supported on this platform",
+			)
+		})?;
+		Ok(directory | cloexec | if follow { 0 } else { nofollow })
+	}
+
+	fn fd_to_file(fd: c_int) -> io::Result<File> {
+		if fd < 0 {
+			return Err(io::Error::last_os_error());
+		}
+		// SAFETY: `fd` was just opened and nothing else owns it
+		Ok(unsafe { File::from_raw_fd(fd) })
+	}
+
+	pub fn uid() -> u32 {
+		// SAFETY: getuid() has no preconditions and can't fail
+		unsafe { getuid() }
+	}
+
+	pub fn euid() -> u32 {
+		// SAFETY: geteuid() has no preconditions and can't fail
+		unsafe { geteuid() }
+	}
+
+	pub fn gid() -> u32 {
+		// SAFETY: getgid() has no preconditions and can't fail
+		unsafe { getgid() }
+	}
+
+	pub fn egid() -> u32 {
+		// SAFETY: getegid() has no preconditions and can't fail
+		unsafe { getegid() }
+	}
+
+	pub fn open_root() -> io::Result<File> {
+		let flags = flags(true)?;
+		// SAFETY: the path is NUL-terminated
+		fd_to_file(unsafe { open(c"/".as_ptr(), flags) })
+	}
+
+	/// Opens the directory `name` inside `dir`, following a final symlink only if `follow`.
+	pub fn open_dir_at(dir: &File, name: &CStr, follow: bool) -> io::Result<File> {
+		let flags = flags(follow)?;
+		// SAFETY: `dir` is an open directory and `name` is NUL-terminated
+		fd_to_file(unsafe { openat(dir.as_raw_fd(), name.as_ptr(), flags) })
+	}
+
+	pub fn mkdir_at(dir: &File, name: &CStr, mode: u32) -> io::Result<()> {
+		// SAFETY: `dir` is an open directory and `name` is NUL-terminated
+		if unsafe { mkdirat(dir.as_raw_fd(), name.as_ptr(), mode as ModeT) } < 0 {
+			return Err(io::Error::last_os_error());
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,129 @@
+//! Errors explaining why a directory is missing
+
+use crate::Resolver;
+use crate::emulate::Platform;
+use std::ffi::OsString;
+use std::fmt;
+
+/// Why a directory couldn't be resolved.
+///
+/// Returned by the `try_*` functions, such as [`try_cache_dir()`](crate::try_cache_dir).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DirError {
+	/// The home directory is unknown, e.g. `$HOME` or `%USERPROFILE%` is unset.
+	HomeNotSet,
+	/// An environment variable the directory comes from is unset or empty.
+	EnvVarNotSet {
+		/// The variable's name.
+		var: String,
+	},
+	/// An environment variable is set, but to something that isn't a usable path,
+	/// such as a relative path in an XDG variable.
+	EnvVarInvalid {
+		/// The variable's name.
+		var: String,
+		/// The rejected value.
+		value: OsString,
+	},
+	/// Android paths were never provided, either with `init_android()` or the
+	/// `android-auto` feature.
+	NotInitialized,
+	/// The platform has no such directory, e.g. [`library_dir()`](crate::library_dir)
+	/// on Linux or anything on WASM.
+	Unsupported {
+		/// The platform that was asked.
+		platform: Platform,
+	},
+}
+
+impl DirError {
+	/// Explain a missing directory that comes from `var`: a set but rejected value is
+	/// invalid, otherwise the directory is missing for the reason in `unset`.
+	pub(crate) fn from_env(var: &str, value: Option<OsString>, unset: DirError) -> DirError {
+		match value {
+			Some(value) if !value.is_empty() => DirError::EnvVarInvalid {
+				var: var.to_string(),
+				value,
+			},
+			_ => unset,
+		}
+	}
+
+	pub(crate) fn env_var_not_set(var: &str) -> DirError {
+		DirError::EnvVarNotSet {
+			var: var.to_string(),
+		}
+	}
+}
+
+/// Shorthand for a directory the resolver's platform doesn't have.
+pub(crate) fn unsupported<T>(r: &Resolver) -> Result<T, DirError> {
+	Err(DirError::Unsupported {
+		platform: r.platform(),
+	})
+}
+
+impl fmt::Display for DirError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			DirError::HomeNotSet => f.write_str("the home directory could not be determined"),
+			DirError::EnvVarNotSet { var } => write!(f, "{var} is not set"),
+			DirError::EnvVarInvalid { var, value } => {
+				write!(f, "{var} is set to {value:?}, which is not a usable path")
+			}
+			DirError::NotInitialized => f.write_str(
+				"Android directories are not initialized; call init_android() \
+				 or enable the android-auto feature",
+			),
+			DirError::Unsupported { platform } => {
+				write!(f, "directory is not available on {platform}")
+			}
+		}
+	}
+}
+
+impl std::error::Error for DirError {}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_from_env() {
+		assert_eq!(
+			DirError::from_env("XDG_RUNTIME_DIR", Some("run".into()), DirError::HomeNotSet),
+			DirError::EnvVarInvalid {
+				var: "XDG_RUNTIME_DIR".to_string(),
+				value: "run".into(),
+			}
+		);
+		assert_eq!(
+			DirError::from_env("XDG_RUNTIME_DIR", Some("".into()), DirError::HomeNotSet),
+			DirError::HomeNotSet
+		);
+		assert_eq!(
+			DirError::from_env("XDG_RUNTIME_DIR", None, DirError::HomeNotSet),
+			DirError::HomeNotSet
+		);
+	}
+
+	#[test]
+	fn test_display() {
+		assert_eq!(
+			DirError::env_var_not_set("APPDATA").to_string(),
+			"APPDATA is not set"
+		);
+		assert_eq!(
+			DirError::Unsupported {
+				platform: Platform::Wasm
+			}
+			.to_string(),
+			"directory is not available on wasm"
+		);
+	}
+}
@@ -0,0 +1,215 @@
+//! Where each directory came from
+//!
+//! Backends return a [`Found`] path together with its [`Source`], and
+//! [`explain()`](crate::explain) reports both.
+
+use crate::{Dir, DirError, SearchPathDomain};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Where a directory's path came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Source {
+	/// Set with [`set_override()`](crate::set_override).
+	Override,
+	/// Portable mode put the directory beside the executable.
+	Portable {
+		/// The folder holding the portable directories.
+		root: PathBuf,
+	},
+	/// Supplied to the [`Resolver`](crate::Resolver) with `with_home()` or
+	/// `with_android_dirs()`.
+	Injected,
+	/// An environment variable, e.g. `XDG_CACHE_HOME` or `APPDATA`.
+	EnvVar {
+		/// The variable's name.
+		var: String,
+		/// The variable's raw value.
+		value: OsString,
+	},
+	/// The user's entry in the password database, used because `$HOME` is unset
+	/// (see [`set_passwd_fallback()`](crate::set_passwd_fallback)) or because the
+	/// resolver came from [`for_user()`](crate::for_user).
+	Passwd {
+		/// The user whose entry was read.
+		uid: u32,
+	},
+	/// The XDG default under the home directory, e.g. `$HOME/.cache`.
+	XdgDefault {
+		/// The home directory the default is relative to.
+		home: PathBuf,
+	},
+	/// A line of the `user-dirs.dirs` file maintained by xdg-user-dirs.
+	UserDirsFile {
+		/// The file that was read.
+		path: PathBuf,
+		/// The 1-based line number of the entry that was used.
+		line: usize,
+	},
+	/// Apple's sysdir API, searched in the given domain.
+	Sysdir {
+		/// The domain that was searched.
+		domain: SearchPathDomain,
+	},
+	/// Android paths passed to `init_android()` or `init_android_with_cache()`.
+	AndroidInit,
+	/// Android paths detected through ndk-context (the `android-auto` feature).
+	AndroidNdkContext,
+	/// A fixed platform default, e.g. `/tmp`.
+	PlatformDefault,
+	/// The directory couldn't be resolved.
+	Missing(DirError),
+}
+
+impl Source {
+	pub(crate) fn env(var: &str, value: OsString) -> Source {
+		Source::EnvVar {
+			var: var.to_string(),
+			value,
+		}
+	}
+}
+
+impl fmt::Display for Source {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Source::Override => f.write_str("set with set_override()"),
+			Source::Portable { root } => write!(f, "portable mode in {}", root.display()),
+			Source::Injected => f.write_str("supplied to the resolver"),
+			Source::EnvVar { var, value } => write!(f, "${var} = {value:?}"),
+			Source::Passwd { uid } => write!(f, "password database entry for uid {uid}"),
+			Source::XdgDefault { home } => write!(f, "XDG default under {}", home.display()),
+			Source::UserDirsFile { path, line } => write!(f, "{}:{line}", path.display()),
+			Source::Sysdir { domain } => write!(f, "sysdir, {domain:?} domain"),
+			Source::AndroidInit => f.write_str("init_android()"),
+			Source::AndroidNdkContext => f.write_str("ndk-context"),
+			Source::PlatformDefault => f.write_str("platform default"),
+			Source::Missing(err) => write!(f, "missing: {err}"),
+		}
+	}
+}
+
+/// A directory's path together with where it came from. Returned by
+/// [`explain()`](crate::explain).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Resolution {
+	dir: Dir,
+	path: Option<PathBuf>,
+	source: Source,
+}
+
+impl Resolution {
+	pub(crate) fn new(dir: Dir, result: Result<Found, DirError>) -> Self {
+		match result {
+			Ok(found) => Self {
+				dir,
+				path: Some(found.path),
+				source: found.source,
+			},
+			Err(err) => Self {
+				dir,
+				path: None,
+				source: Source::Missing(err),
+			},
+		}
+	}
+
+	/// Returns the directory kind that was resolved.
+	pub fn dir(&self) -> Dir {
+		self.dir
+	}
+
+	/// Returns the path, or `None` if the directory couldn't be resolved.
+	pub fn path(&self) -> Option<&Path> {
+		self.path.as_deref()
+	}
+
+	/// Returns where the path came from, or [`Source::Missing`] with the reason it's absent.
+	pub fn source(&self) -> &Source {
+		&self.source
+	}
+
+	pub(crate) fn as_result(&self) -> Result<&Path, &DirError> {
+		match (&self.path, &self.source) {
+			(Some(path), _) => Ok(path),
+			(None, Source::Missing(err)) => Err(err),
+			(None, _) => unreachable!("a resolution without a path is always missing"),
+		}
+	}
+
+	pub(crate) fn to_found(&self) -> Result<Found, DirError> {
+		self.as_result()
+			.map(|path| Found::new(path.to_path_buf(), self.source.clone()))
+			.map_err(DirError::clone)
+	}
+}
+
+impl fmt::Display for Resolution {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.path {
+			Some(path) => write!(f, "{}: {} ({})", self.dir, path.display(), self.source),
+			None => write!(f, "{}: {}", self.dir, self.source),
+		}
+	}
+}
+
+/// A path a backend found, and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Found {
+	pub path: PathBuf,
+	pub source: Source,
+}
+
+impl Found {
+	pub fn new(path: PathBuf, source: Source) -> Self {
+		Self { path, source }
+	}
+
+	/// Derive a subdirectory, which keeps the source of its parent.
+	pub fn join<P: AsRef<Path>>(self, rest: P) -> Self {
+		self.map(|path| path.join(rest))
+	}
+
+	pub fn map(self, f: impl FnOnce(PathBuf) -> PathBuf) -> Self {
+		Self {
+			path: f(self.path),
+			source: self.source,
+		}
+	}
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_display() {
+		let found = Found::new(
+			PathBuf::from("/home/alice/.local/share"),
+			Source::XdgDefault {
+				home: PathBuf::from("/home/alice"),
+			},
+		);
+		let resolution = Resolution::new(Dir::Font, Ok(found.join("fonts")));
+		assert_eq!(
+			resolution.to_string(),
+			"font: /home/alice/.local/share/fonts (XDG default under /home/alice)"
+		);
+
+		let resolution = Resolution::new(
+			Dir::Runtime,
+			Err(DirError::env_var_not_set("XDG_RUNTIME_DIR")),
+		);
+		assert_eq!(resolution.path(), None);
+		assert_eq!(
+			resolution.to_string(),
+			"runtime: missing: XDG_RUNTIME_DIR is not set"
+		);
+	}
+}
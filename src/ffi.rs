@@ -0,0 +1,268 @@
+//! C ABI for non-Rust consumers
+//!
+//! The declarations, and the ownership rules for every function, are in
+//! `include/sysdirs.h`. Build a library to link against with
+//! `cargo rustc --release --lib --features ffi --crate-type staticlib` (or `cdylib`).
+
+use crate::{Dir, DirError};
+use std::borrow::Cow;
+use std::ffi::{c_char, c_int};
+use std::path::Path;
+
+// Keep in sync with the `SYSDIRS_ERR_*` defines in sysdirs.h
+const ERR_HOME_NOT_SET: isize = -1;
+const ERR_ENV_VAR_NOT_SET: isize = -2;
+const ERR_ENV_VAR_INVALID: isize = -3;
+const ERR_NOT_INITIALIZED: isize = -4;
+const ERR_UNSUPPORTED: isize = -5;
+const ERR_INVALID_ARGUMENT: isize = -6;
+
+fn error_code(err: &DirError) -> isize {
+	match err {
+		DirError::HomeNotSet => ERR_HOME_NOT_SET,
+		DirError::EnvVarNotSet { .. } => ERR_ENV_VAR_NOT_SET,
+		DirError::EnvVarInvalid { .. } => ERR_ENV_VAR_INVALID,
+		DirError::NotInitialized => ERR_NOT_INITIALIZED,
+		DirError::Unsupported { .. } => ERR_UNSUPPORTED,
+	}
+}
+
+/// The bytes handed to C: the raw `OsStr` on Unix, UTF-8 elsewhere.
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+	use std::os::unix::ffi::OsStrExt;
+	Cow::Borrowed(path.as_os_str().as_bytes())
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Cow<'_, [u8]> {
+	match path.to_string_lossy() {
+		Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+		Cow::Owned(s) => Cow::Owned(s.into_bytes()),
+	}
+}
+
+/// Copies `bytes` and a NUL into `buf` if they fit, returning the length without the NUL.
+///
+/// # Safety
+///
+/// `buf` must be null or valid for writes of `len` bytes.
+unsafe fn copy_out(bytes: &[u8], buf: *mut c_char, len: usize) -> isize {
+	if bytes.len() < len {
+		// SAFETY: the caller guarantees `len` writable bytes, and `bytes.len() + 1 <= len`
+		unsafe {
+			std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.cast::<u8>(), bytes.len());
+			*buf.add(bytes.len()) = 0;
+		}
+	}
+	bytes.len() as isize
+}
+
+/// See `sysdirs_get` in sysdirs.h.
+///
+/// # Safety
+///
+/// `buf` must be null or valid for writes of `len` bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysdirs_get(kind: c_int, buf: *mut c_char, len: usize) -> isize {
+	let Some(&dir) = usize::try_from(kind).ok().and_then(|i| Dir::ALL.get(i)) else {
+		return ERR_INVALID_ARGUMENT;
+	};
+	if buf.is_null() && len > 0 {
+		return ERR_INVALID_ARGUMENT;
+	}
+	match crate::try_get(dir) {
+		// SAFETY: forwarded from the caller
+		Ok(path) => unsafe { copy_out(&path_bytes(&path), buf, len) },
+		Err(err) => error_code(&err),
+	}
+}
+
+/// See `sysdirs_error_message` in sysdirs.h.
+#[unsafe(no_mangle)]
+pub extern "C" fn sysdirs_error_message(code: isize) -> *const c_char {
+	let message = match code {
+		ERR_HOME_NOT_SET => c"the home directory could not be determined",
+		ERR_ENV_VAR_NOT_SET => c"an environment variable the directory comes from is not set",
+		ERR_ENV_VAR_INVALID => {
+			c"an environment variable is set to something that is not a usable path"
+		}
+		ERR_NOT_INITIALIZED => {
+			c"Android directories are not initialized; call sysdirs_init_android()"
+		}
+		ERR_UNSUPPORTED => c"the directory is not available on this platform",
+		ERR_INVALID_ARGUMENT => c"invalid argument",
+		_ if code >= 0 => c"success",
+		_ => c"unknown error",
+	};
+	message.as_ptr()
+}
+
+// =============================================================================
+// Android Initialization
+// =============================================================================
+
+/// Reads a NUL-terminated UTF-8 string, or `None` if it's null or not UTF-8.
+///
+/// # Safety
+///
+/// `s` must be null or point to a NUL-terminated string.
+#[cfg(target_os = "android")]
+unsafe fn c_str<'a>(s: *const c_char) -> Option<&'a str> {
+	if s.is_null() {
+		return None;
+	}
+	// SAFETY: the caller guarantees a NUL-terminated string
+	unsafe { std::ffi::CStr::from_ptr(s) }.to_str().ok()
+}
+
+/// See `sysdirs_init_android` in sysdirs.h.
+///
+/// # Safety
+///
+/// `files_dir` must be null or point to a NUL-terminated string.
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysdirs_init_android(files_dir: *const c_char) -> c_int {
+	// SAFETY: forwarded from the caller
+	match unsafe { c_str(files_dir) } {
+		Some(files_dir) => {
+			crate::init_android(files_dir);
+			0
+		}
+		None => ERR_INVALID_ARGUMENT as c_int,
+	}
+}
+
+/// See `sysdirs_init_android_with_cache` in sysdirs.h.
+///
+/// # Safety
+///
+/// Both arguments must be null or point to NUL-terminated strings.
+#[cfg(target_os = "android")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sysdirs_init_android_with_cache(
+	files_dir: *const c_char,
+	cache_dir: *const c_char,
+) -> c_int {
+	// SAFETY: forwarded from the caller
+	match unsafe { (c_str(files_dir), c_str(cache_dir)) } {
+		(Some(files_dir), Some(cache_dir)) => {
+			crate::init_android_with_cache(files_dir, cache_dir);
+			0
+		}
+		_ => ERR_INVALID_ARGUMENT as c_int,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::ffi::CStr;
+	use std::ptr;
+
+	const HEADER: &str = include_str!("../include/sysdirs.h");
+
+	/// Returns the value the header gives `name`, as either an enumerator or a define.
+	fn header_value(name: &str) -> Option<isize> {
+		HEADER.lines().find_map(|line| {
+			let line = line.trim().trim_start_matches("#define").trim();
+			let rest = line.strip_prefix(name)?;
+			let value = rest
+				.trim_start_matches([' ', '\t', '='])
+				.trim_end_matches(',');
+			value.trim_matches(['(', ')']).parse().ok()
+		})
+	}
+
+	#[test]
+	fn test_header_kinds_match_dir_all() {
+		for (i, dir) in Dir::ALL.iter().enumerate() {
+			let name = format!(
+				"SYSDIRS_{}",
+				dir.name().to_ascii_uppercase().replace('-', "_")
+			);
+			assert_eq!(header_value(&name), Some(i as isize), "{name}");
+		}
+	}
+
+	#[test]
+	fn test_header_error_codes_match() {
+		for (name, code) in [
+			("SYSDIRS_ERR_HOME_NOT_SET", ERR_HOME_NOT_SET),
+			("SYSDIRS_ERR_ENV_VAR_NOT_SET", ERR_ENV_VAR_NOT_SET),
+			("SYSDIRS_ERR_ENV_VAR_INVALID", ERR_ENV_VAR_INVALID),
+			("SYSDIRS_ERR_NOT_INITIALIZED", ERR_NOT_INITIALIZED),
+			("SYSDIRS_ERR_UNSUPPORTED", ERR_UNSUPPORTED),
+			("SYSDIRS_ERR_INVALID_ARGUMENT", ERR_INVALID_ARGUMENT),
+		] {
+			assert_eq!(header_value(name), Some(code), "{name}");
+		}
+	}
+
+	#[test]
+	fn test_copy_out_fits() {
+		let mut buf = [0x7f as c_char; 8];
+		let n = unsafe { copy_out(b"/tmp", buf.as_mut_ptr(), buf.len()) };
+		assert_eq!(n, 4);
+		let written = unsafe { CStr::from_ptr(buf.as_ptr()) };
+		assert_eq!(written.to_bytes(), b"/tmp");
+	}
+
+	#[test]
+	fn test_copy_out_too_small_leaves_buffer() {
+		// No room for the NUL
+		let mut buf = [0x7f as c_char; 4];
+		let n = unsafe { copy_out(b"/tmp", buf.as_mut_ptr(), buf.len()) };
+		assert_eq!(n, 4);
+		assert!(buf.iter().all(|&b| b == 0x7f));
+
+		// Querying the size
+		assert_eq!(unsafe { copy_out(b"/tmp", ptr::null_mut(), 0) }, 4);
+	}
+
+	#[test]
+	fn test_get_invalid_arguments() {
+		let mut buf = [0 as c_char; 8];
+		assert_eq!(
+			unsafe { sysdirs_get(-1, buf.as_mut_ptr(), buf.len()) },
+			ERR_INVALID_ARGUMENT
+		);
+		let past_end = Dir::ALL.len() as c_int;
+		assert_eq!(
+			unsafe { sysdirs_get(past_end, buf.as_mut_ptr(), buf.len()) },
+			ERR_INVALID_ARGUMENT
+		);
+		assert_eq!(
+			unsafe { sysdirs_get(0, ptr::null_mut(), 8) },
+			ERR_INVALID_ARGUMENT
+		);
+	}
+
+	#[test]
+	fn test_get_matches_try_get() {
+		for (i, &dir) in Dir::ALL.iter().enumerate() {
+			let n = unsafe { sysdirs_get(i as c_int, ptr::null_mut(), 0) };
+			match crate::try_get(dir) {
+				Ok(path) => {
+					let mut buf = vec![0 as c_char; n as usize + 1];
+					assert_eq!(
+						unsafe { sysdirs_get(i as c_int, buf.as_mut_ptr(), buf.len()) },
+						n
+					);
+					let written = unsafe { CStr::from_ptr(buf.as_ptr()) };
+					assert_eq!(written.to_bytes(), &*path_bytes(&path));
+				}
+				Err(err) => assert_eq!(n, error_code(&err)),
+			}
+		}
+	}
+
+	#[test]
+	fn test_error_messages() {
+		let message = |code| unsafe { CStr::from_ptr(sysdirs_error_message(code)) };
+		assert_eq!(message(ERR_INVALID_ARGUMENT), c"invalid argument");
+		assert_eq!(message(0), c"success");
+		assert_eq!(message(-100), c"unknown error");
+	}
+}
@@ -33,6 +33,64 @@
 //! // Android: Some(<filesDir>/cache)
 //! ```
 //!
+//! ## Explaining Missing Directories
+//!
+//! Every directory function has a `try_*` variant, such as [`try_cache_dir()`], that
+//! returns a [`DirError`] saying why the directory is missing instead of `None`.
+//!
+//! ## Explaining Where a Directory Came From
+//!
+//! [`explain()`] returns a [`Resolution`]: the path together with its [`Source`], such
+//! as the env var and its raw value, the XDG default, or a line of `user-dirs.dirs`.
+//!
+//! ## Checking for Problems
+//!
+//! [`doctor()`] reports directories that are missing, unwritable, world-writable or
+//! owned by another user, and XDG variables that were ignored as invalid.
+//!
+//! ## Application Directories
+//!
+//! [`AppDirs`] scopes the base directories to a single application, following each
+//! platform's naming convention instead of a plain `.join("my-app")`:
+//!
+//! ```rust
+//! let app = sysdirs::AppDirs::new("com", "Foo Corp", "Bar App");
+//! app.cache_dir();
+//! // Lin: Some(/home/alice/.cache/barapp)
+//! // Win: Some(C:\Users\Alice\AppData\Local\Foo Corp\Bar App\cache)
+//! // Mac: Some(/Users/Alice/Library/Caches/com.Foo-Corp.Bar-App)
+//! ```
+//!
+//! ## Injecting the Environment
+//!
+//! The free functions read the process environment. A [`Resolver`] answers the same
+//! questions from a caller-supplied environment and home directory, which keeps tests
+//! deterministic without `std::env::set_var`.
+//!
+//! ## Overriding Directories
+//!
+//! [`set_override()`] redirects a single directory kind for the whole process, e.g. for a
+//! `--data-dir` flag, and [`scoped_override()`] does the same until its guard is dropped.
+//!
+//! ## Portable Mode
+//!
+//! When a `portable.txt` marker sits next to the executable, or after
+//! [`set_portable_mode()`] enables it, the config, data, cache, state and temp directories
+//! resolve to `config`, `data`, `cache`, `state` and `temp` beside the executable.
+//! [`is_portable()`] reports whether this is the case.
+//!
+//! ## Snapshots
+//!
+//! A [`Snapshot`] resolves every directory once and is cheap to clone and compare.
+//! [`freeze()`] captures one for the rest of the process and serves the free
+//! functions from it. [`DirSet`] holds the same results in named fields, and with the
+//! `serde` feature can be serialized.
+//!
+//! ## Emulating Other Platforms
+//!
+//! [`Resolver::with_platform()`] computes the directories of another platform, e.g.
+//! Windows paths on a Linux build machine. See the [`emulate`] module.
+//!
 //! ## Android Setup
 //!
 //! There are two ways to use sysdirs on Android:
@@ -91,7 +149,10 @@ use std::path::PathBuf;
 // Path Extension Trait
 // =============================================================================
 
-/// Extension trait for `Option<PathBuf>` that adds chainable path operations.
+mod ensure;
+
+/// Extension trait for `Option<PathBuf>` and `Result<PathBuf, DirError>` that adds
+/// chainable path operations.
 ///
 /// This trait makes it easy to work with directory paths in a fluent style:
 ///
@@ -119,12 +180,13 @@ pub trait PathExt {
 	///     .join("cache");
 	/// // Linux: Some(/home/alice/.local/share/my-app/cache)
 	/// ```
-	fn join<P: AsRef<Path>>(self, path: P) -> Option<PathBuf>;
+	fn join<P: AsRef<Path>>(self, path: P) -> Self;
 
 	/// Ensures the directory exists, creating it if necessary.
 	///
 	/// Returns the path if successful, or an error if:
-	/// - The original `Option` was `None` (directory not available on this platform)
+	/// - The original `Option` was `None` (directory not available on this platform), or
+	///   the original `Result` was an `Err`, whose [`DirError`] becomes the error's source
 	/// - Directory creation failed (permissions, disk full, etc.)
 	///
 	/// # Example
@@ -138,96 +200,149 @@ pub trait PathExt {
 	/// // Directory now exists, ready to use
 	/// ```
 	fn ensure(self) -> io::Result<PathBuf>;
+
+	/// Like [`ensure()`](PathExt::ensure), but creates directories that only the
+	/// current user can access (mode 0700 on Unix).
+	///
+	/// See [`ensure_with_mode()`](PathExt::ensure_with_mode) for how existing
+	/// directories are treated.
+	///
+	/// # Example
+	///
+	/// ```rust,ignore
+	/// use sysdirs::PathExt;
+	///
+	/// let tokens = sysdirs::state_dir()
+	///     .join("my-app")
+	///     .join("credentials")
+	///     .ensure_private()?;
+	/// ```
+	fn ensure_private(self) -> io::Result<PathBuf>
+	where
+		Self: Sized,
+	{
+		self.ensure_with_mode(0o700)
+	}
+
+	/// Like [`ensure()`](PathExt::ensure), but creates every missing directory with
+	/// the Unix permissions `mode`, regardless of the umask.
+	///
+	/// Parents that already exist, such as `$HOME`, are left alone. If the directory
+	/// itself already exists with permissions looser than `mode`, e.g. 0755 when 0700
+	/// was asked for, this returns a [`PermissionDenied`](io::ErrorKind::PermissionDenied)
+	/// error rather than changing them behind the caller's back.
+	///
+	/// On other platforms `mode` is ignored and this is the same as `ensure()`.
+	fn ensure_with_mode(self, mode: u32) -> io::Result<PathBuf>;
+
+	/// Like [`ensure_private()`](PathExt::ensure_private), but safe to use under
+	/// directories other users can write to, such as [`temp_dir()`] (`/tmp`).
+	///
+	/// On Unix the path is walked one component at a time with `openat()` and
+	/// `O_NOFOLLOW`, so a symlink another user planted, e.g. `/tmp/my-app -> ~/.ssh`,
+	/// is refused rather than followed. Symlinks in directories only root or the
+	/// current user can write to, like `/tmp -> private/tmp` on macOS, are still
+	/// followed. Fails with [`PermissionDenied`](io::ErrorKind::PermissionDenied) if
+	/// the directory, or any directory below a world-writable one, is owned by
+	/// another user, and with [`InvalidInput`](io::ErrorKind::InvalidInput) for
+	/// relative paths or paths containing `..`.
+	///
+	/// On other platforms this is the same as `ensure()`.
+	///
+	/// # Example
+	///
+	/// ```rust,ignore
+	/// use sysdirs::PathExt;
+	///
+	/// let sockets = sysdirs::temp_dir().join("my-daemon").ensure_secure()?;
+	/// ```
+	fn ensure_secure(self) -> io::Result<PathBuf>;
 }
 
 impl PathExt for Option<PathBuf> {
-	fn join<P: AsRef<Path>>(self, path: P) -> Option<PathBuf> {
+	fn join<P: AsRef<Path>>(self, path: P) -> Self {
 		self.map(|p| p.join(path))
 	}
 
 	fn ensure(self) -> io::Result<PathBuf> {
-		match self {
-			Some(path) => {
-				std::fs::create_dir_all(&path)?;
-				Ok(path)
-			}
-			None => Err(io::Error::new(
-				io::ErrorKind::NotFound,
-				"directory not available on this platform",
-			)),
-		}
+		let path = self.ok_or_else(|| unavailable(None))?;
+		std::fs::create_dir_all(&path)?;
+		Ok(path)
+	}
+
+	fn ensure_with_mode(self, mode: u32) -> io::Result<PathBuf> {
+		let path = self.ok_or_else(|| unavailable(None))?;
+		ensure::create_with_mode(&path, mode)?;
+		Ok(path)
+	}
+
+	fn ensure_secure(self) -> io::Result<PathBuf> {
+		let path = self.ok_or_else(|| unavailable(None))?;
+		ensure::create_secure(&path, 0o700)?;
+		Ok(path)
+	}
+}
+
+impl PathExt for Result<PathBuf, DirError> {
+	fn join<P: AsRef<Path>>(self, path: P) -> Self {
+		self.map(|p| p.join(path))
+	}
+
+	fn ensure(self) -> io::Result<PathBuf> {
+		let path = self.map_err(|err| unavailable(Some(err)))?;
+		std::fs::create_dir_all(&path)?;
+		Ok(path)
+	}
+
+	fn ensure_with_mode(self, mode: u32) -> io::Result<PathBuf> {
+		let path = self.map_err(|err| unavailable(Some(err)))?;
+		ensure::create_with_mode(&path, mode)?;
+		Ok(path)
+	}
+
+	fn ensure_secure(self) -> io::Result<PathBuf> {
+		let path = self.map_err(|err| unavailable(Some(err)))?;
+		ensure::create_secure(&path, 0o700)?;
+		Ok(path)
+	}
+}
+
+/// The error for ensuring a directory that didn't resolve, with the reason as its
+/// source when there is one.
+fn unavailable(err: Option<DirError>) -> io::Error {
+	match err {
+		Some(err) => io::Error::new(io::ErrorKind::NotFound, err),
+		None => io::Error::new(
+			io::ErrorKind::NotFound,
+			"directory not available on this platform",
+		),
 	}
 }
 
+// =============================================================================
+// Application Directories
+// =============================================================================
+
+mod app_dirs;
+pub use app_dirs::AppDirs;
+
 // =============================================================================
 // Platform Modules
 // =============================================================================
 
-#[cfg(any(
-	target_os = "macos",
-	target_os = "ios",
-	target_os = "tvos",
-	target_os = "watchos",
-	target_os = "visionos"
-))]
+// Every backend is compiled on every target so that `emulate` can run them.
+// A `Resolver` picks one at runtime, defaulting to the host platform.
+mod android;
 mod apple;
-#[cfg(any(
-	target_os = "macos",
-	target_os = "ios",
-	target_os = "tvos",
-	target_os = "watchos",
-	target_os = "visionos"
-))]
-use apple as platform;
-
-#[cfg(target_os = "linux")]
 mod linux;
-#[cfg(target_os = "linux")]
-use linux as platform;
-
-#[cfg(target_os = "windows")]
+mod unix;
+mod wasm;
 mod windows;
-#[cfg(target_os = "windows")]
-use windows as platform;
 
-#[cfg(target_os = "android")]
-mod android;
-#[cfg(target_os = "android")]
-use android as platform;
-
-#[cfg(target_arch = "wasm32")]
-mod wasm;
-#[cfg(target_arch = "wasm32")]
-use wasm as platform;
-
-// Fallback for other platforms (FreeBSD, etc.)
-#[cfg(not(any(
-	target_os = "macos",
-	target_os = "ios",
-	target_os = "tvos",
-	target_os = "watchos",
-	target_os = "visionos",
-	target_os = "linux",
-	target_os = "windows",
-	target_os = "android",
-	target_arch = "wasm32"
-)))]
-mod unix;
-#[cfg(not(any(
-	target_os = "macos",
-	target_os = "ios",
-	target_os = "tvos",
-	target_os = "watchos",
-	target_os = "visionos",
-	target_os = "linux",
-	target_os = "windows",
-	target_os = "android",
-	target_arch = "wasm32"
-)))]
-use unix as platform;
+pub mod emulate;
 
 // =============================================================================
-// Apple Search Path Domain (Apple platforms only)
+// Apple Search Path Domain
 // =============================================================================
 
 /// Search path domain for Apple platforms.
@@ -235,15 +350,9 @@ use unix as platform;
 /// Controls which domain to search when looking up directories on macOS, iOS, etc.
 /// Defaults to `User`.
 ///
-/// This is only available on Apple platforms.
-#[cfg(any(
-	target_os = "macos",
-	target_os = "ios",
-	target_os = "tvos",
-	target_os = "watchos",
-	target_os = "visionos"
-))]
+/// On other platforms this only affects [emulated](crate::emulate) Apple lookups.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SearchPathDomain {
 	/// User's home directory (e.g., ~/Library/...)
 	#[default]
@@ -261,7 +370,8 @@ pub enum SearchPathDomain {
 /// By default, sysdirs uses the `User` domain which returns paths like `~/Library/Caches`.
 /// System utilities or admin tools may want to use `Local` or `System` domains.
 ///
-/// This function is only available on Apple platforms.
+/// The domain is per-thread. On other platforms it only affects [emulated](crate::emulate)
+/// Apple lookups.
 ///
 /// # Example
 ///
@@ -275,15 +385,8 @@ pub enum SearchPathDomain {
 /// set_domain(SearchPathDomain::Local);
 /// cache_dir(); // /Library/Caches
 /// ```
-#[cfg(any(
-	target_os = "macos",
-	target_os = "ios",
-	target_os = "tvos",
-	target_os = "watchos",
-	target_os = "visionos"
-))]
 pub fn set_domain(domain: SearchPathDomain) {
-	platform::set_domain(domain);
+	apple::set_domain(domain);
 }
 
 // =============================================================================
@@ -305,7 +408,7 @@ pub fn set_domain(domain: SearchPathDomain) {
 /// ```
 #[cfg(target_os = "android")]
 pub fn init_android(files_dir: &str) {
-	platform::init_android(files_dir);
+	android::init_android(files_dir);
 }
 
 /// Initialize Android-specific paths with separate directories.
@@ -326,7 +429,146 @@ pub fn init_android(files_dir: &str) {
 /// ```
 #[cfg(target_os = "android")]
 pub fn init_android_with_cache(files_dir: &str, cache_dir: &str) {
-	platform::init_android_with_cache(files_dir, cache_dir);
+	android::init_android_with_cache(files_dir, cache_dir);
+}
+
+// =============================================================================
+// Generic Lookup
+// =============================================================================
+
+mod dir;
+pub use dir::{Dir, ParseDirError};
+
+mod resolver;
+pub use resolver::Resolver;
+
+mod error;
+pub use error::DirError;
+mod explain;
+pub use explain::{Resolution, Source};
+
+mod overrides;
+pub use overrides::{
+	OverrideGuard, clear_all_overrides, clear_override, scoped_override, set_override,
+};
+
+mod portable;
+pub use portable::{
+	DEFAULT_PORTABLE_MARKER, PortableMode, is_portable, portable_dir, set_portable_marker,
+	set_portable_mode,
+};
+
+mod snapshot;
+pub use snapshot::{Snapshot, freeze, frozen};
+
+mod dir_set;
+pub use dir_set::DirSet;
+
+mod doctor;
+pub use doctor::{Finding, Problem, Report, Severity};
+
+mod runtime;
+pub use runtime::RuntimeDir;
+
+mod passwd;
+pub use passwd::{for_uid, for_user, passwd_fallback_enabled, set_passwd_fallback};
+
+mod sudo;
+pub use sudo::{Elevation, OriginalUser, SudoPolicy, original_user, set_sudo_policy, sudo_policy};
+
+mod secure;
+pub use secure::{is_secure_execution, set_secure_mode};
+
+#[cfg(feature = "ffi")]
+mod ffi;
+
+/// Returns the path for a directory kind chosen at runtime.
+///
+/// This is equivalent to calling the matching directory function, e.g.
+/// `get(Dir::Cache)` returns the same value as [`cache_dir()`]. Like every directory
+/// function, it returns the [override](set_override) for `dir` if one is set.
+///
+/// # Example
+///
+/// ```rust
+/// use sysdirs::Dir;
+///
+/// let dir: Dir = "data".parse().unwrap();
+/// let path = sysdirs::get(dir);
+/// // Linux: Some(/home/alice/.local/share)
+/// ```
+pub fn get(dir: Dir) -> Option<PathBuf> {
+	Resolver::from_process_env().get(dir)
+}
+
+/// Returns the path for a directory kind chosen at runtime, or why it is missing.
+///
+/// This is the `Result` counterpart of [`get()`], and what every `try_*` function calls.
+///
+/// # Example
+///
+/// ```rust
+/// use sysdirs::Dir;
+///
+/// match sysdirs::try_get(Dir::Runtime) {
+///     Ok(path) => println!("runtime dir: {}", path.display()),
+///     Err(err) => eprintln!("no runtime dir: {err}"),
+/// }
+/// ```
+pub fn try_get(dir: Dir) -> Result<PathBuf, DirError> {
+	Resolver::from_process_env().try_get(dir)
+}
+
+/// Returns the path for a directory kind together with where it came from.
+///
+/// Useful for debugging "why is my config over there?": the [`Source`] names the
+/// env var and its raw value, the XDG default, the `user-dirs.dirs` line, the
+/// override, and so on.
+///
+/// # Example
+///
+/// ```rust
+/// use sysdirs::Dir;
+///
+/// let resolution = sysdirs::explain(Dir::Config);
+/// println!("{resolution}");
+/// // Linux: config: /home/alice/.config (XDG default under /home/alice)
+/// ```
+pub fn explain(dir: Dir) -> Resolution {
+	Resolver::from_process_env().explain(dir)
+}
+
+/// Checks every directory for problems.
+///
+/// Reports directories that don't exist or aren't writable, world-writable directories
+/// and ones owned by another user, a `$XDG_RUNTIME_DIR` that isn't mode `0700`, a
+/// missing home directory, and XDG variables that were ignored as invalid.
+///
+/// # Example
+///
+/// ```rust
+/// let report = sysdirs::doctor();
+/// if !report.is_healthy() {
+///     eprint!("{report}");
+/// }
+/// ```
+pub fn doctor() -> Report {
+	Resolver::from_process_env().doctor()
+}
+
+/// Returns an iterator over every directory kind and its path, in [`Dir::ALL`] order.
+///
+/// # Example
+///
+/// ```rust
+/// for (dir, path) in sysdirs::all() {
+///     if let Some(path) = path {
+///         println!("{dir}: {}", path.display());
+///     }
+/// }
+/// ```
+pub fn all() -> impl Iterator<Item = (Dir, Option<PathBuf>)> {
+	Dir::ALL.iter().map(|&dir| (dir, get(dir)))
 }
 
 // =============================================================================
@@ -346,8 +588,11 @@ pub fn init_android_with_cache(files_dir: &str, cache_dir: &str) {
 /// | iOS     | sandbox container                        | /var/mobile/.../&lt;UUID&gt;    |
 /// | Android | files directory (after init)             | /data/data/com.example/files     |
 /// | WASM    | `None`                                   |                                  |
+///
+/// On Linux, macOS and other Unix, an unset or empty `$HOME` falls back to the user's
+/// entry in the password database, unless [`set_passwd_fallback()`] turns that off.
 pub fn home_dir() -> Option<PathBuf> {
-	platform::home_dir()
+	get(Dir::Home)
 }
 
 /// Returns the path to the user's cache directory.
@@ -364,7 +609,7 @@ pub fn home_dir() -> Option<PathBuf> {
 /// | Android | files/cache (after init)            | /data/data/com.example/files/cache |
 /// | WASM    | `None`                              |                                    |
 pub fn cache_dir() -> Option<PathBuf> {
-	platform::cache_dir()
+	get(Dir::Cache)
 }
 
 /// Returns the path to the user's config directory.
@@ -381,7 +626,7 @@ pub fn cache_dir() -> Option<PathBuf> {
 /// | Android | files directory (after init)          | /data/data/com.example/files               |
 /// | WASM    | `None`                                |                                            |
 pub fn config_dir() -> Option<PathBuf> {
-	platform::config_dir()
+	get(Dir::Config)
 }
 
 /// Returns the path to the user's local config directory.
@@ -398,7 +643,7 @@ pub fn config_dir() -> Option<PathBuf> {
 /// | Android | files directory (after init)          | /data/data/com.example/files               |
 /// | WASM    | `None`                                |                                            |
 pub fn config_local_dir() -> Option<PathBuf> {
-	platform::config_local_dir()
+	get(Dir::ConfigLocal)
 }
 
 /// Returns the path to the user's data directory.
@@ -415,7 +660,7 @@ pub fn config_local_dir() -> Option<PathBuf> {
 /// | Android | files directory (after init)                | /data/data/com.example/files               |
 /// | WASM    | `None`                                      |                                            |
 pub fn data_dir() -> Option<PathBuf> {
-	platform::data_dir()
+	get(Dir::Data)
 }
 
 /// Returns the path to the user's local data directory.
@@ -432,7 +677,7 @@ pub fn data_dir() -> Option<PathBuf> {
 /// | Android | files directory (after init)                | /data/data/com.example/files               |
 /// | WASM    | `None`                                      |                                            |
 pub fn data_local_dir() -> Option<PathBuf> {
-	platform::data_local_dir()
+	get(Dir::DataLocal)
 }
 
 /// Returns the path to the user's executable directory.
@@ -449,7 +694,7 @@ pub fn data_local_dir() -> Option<PathBuf> {
 /// | Android | `None`                                   |                            |
 /// | WASM    | `None`                                   |                            |
 pub fn executable_dir() -> Option<PathBuf> {
-	platform::executable_dir()
+	get(Dir::Executable)
 }
 
 /// Returns the path to the user's preference directory.
@@ -466,7 +711,7 @@ pub fn executable_dir() -> Option<PathBuf> {
 /// | Android | files directory (after init)          | /data/data/com.example/files             |
 /// | WASM    | `None`                                |                                          |
 pub fn preference_dir() -> Option<PathBuf> {
-	platform::preference_dir()
+	get(Dir::Preference)
 }
 
 /// Returns the path to the user's runtime directory.
@@ -483,7 +728,30 @@ pub fn preference_dir() -> Option<PathBuf> {
 /// | Android | `None`             |                 |
 /// | WASM    | `None`             |                 |
 pub fn runtime_dir() -> Option<PathBuf> {
-	platform::runtime_dir()
+	get(Dir::Runtime)
+}
+
+/// Returns [`runtime_dir()`], or a private per-user directory under [`temp_dir()`] when
+/// there is none, e.g. because `$XDG_RUNTIME_DIR` is unset in a cron job or container.
+///
+/// The fallback is `runtime-<uid>` in the temp directory on Unix (`/tmp/runtime-1000`)
+/// and `runtime` elsewhere. It is created if needed with mode 0700, the same way as
+/// [`PathExt::ensure_secure()`], so this fails with
+/// [`PermissionDenied`](io::ErrorKind::PermissionDenied) if another user created it
+/// first or its mode is looser than 0700. The real runtime directory is returned as
+/// is, without being created or checked.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let runtime = sysdirs::runtime_dir_or_fallback()?;
+/// if runtime.is_fallback() {
+///     eprintln!("XDG_RUNTIME_DIR is unset, using {}", runtime.path().display());
+/// }
+/// let socket = runtime.path().join("my-daemon.sock");
+/// ```
+pub fn runtime_dir_or_fallback() -> io::Result<RuntimeDir> {
+	runtime::resolve(try_runtime_dir(), try_temp_dir())
 }
 
 /// Returns the path to the user's state directory.
@@ -500,7 +768,7 @@ pub fn runtime_dir() -> Option<PathBuf> {
 /// | Android | `None`                                      |                            |
 /// | WASM    | `None`                                      |                            |
 pub fn state_dir() -> Option<PathBuf> {
-	platform::state_dir()
+	get(Dir::State)
 }
 
 // =============================================================================
@@ -521,7 +789,7 @@ pub fn state_dir() -> Option<PathBuf> {
 /// | Android | `None`                |                          |
 /// | WASM    | `None`                |                          |
 pub fn audio_dir() -> Option<PathBuf> {
-	platform::audio_dir()
+	get(Dir::Audio)
 }
 
 /// Returns the path to the user's desktop directory.
@@ -538,7 +806,7 @@ pub fn audio_dir() -> Option<PathBuf> {
 /// | Android | `None`                |                          |
 /// | WASM    | `None`                |                          |
 pub fn desktop_dir() -> Option<PathBuf> {
-	platform::desktop_dir()
+	get(Dir::Desktop)
 }
 
 /// Returns the path to the user's document directory.
@@ -555,7 +823,7 @@ pub fn desktop_dir() -> Option<PathBuf> {
 /// | Android | `None`                  |                            |
 /// | WASM    | `None`                  |                            |
 pub fn document_dir() -> Option<PathBuf> {
-	platform::document_dir()
+	get(Dir::Document)
 }
 
 /// Returns the path to the user's download directory.
@@ -572,7 +840,7 @@ pub fn document_dir() -> Option<PathBuf> {
 /// | Android | `None`                  |                            |
 /// | WASM    | `None`                  |                            |
 pub fn download_dir() -> Option<PathBuf> {
-	platform::download_dir()
+	get(Dir::Download)
 }
 
 /// Returns the path to the user's font directory.
@@ -589,7 +857,7 @@ pub fn download_dir() -> Option<PathBuf> {
 /// | Android | `None`                                             |                                    |
 /// | WASM    | `None`                                             |                                    |
 pub fn font_dir() -> Option<PathBuf> {
-	platform::font_dir()
+	get(Dir::Font)
 }
 
 /// Returns the path to the user's picture directory.
@@ -606,7 +874,7 @@ pub fn font_dir() -> Option<PathBuf> {
 /// | Android | `None`                  |                            |
 /// | WASM    | `None`                  |                            |
 pub fn picture_dir() -> Option<PathBuf> {
-	platform::picture_dir()
+	get(Dir::Picture)
 }
 
 /// Returns the path to the user's public directory.
@@ -623,7 +891,7 @@ pub fn picture_dir() -> Option<PathBuf> {
 /// | Android | `None`                  |                            |
 /// | WASM    | `None`                  |                            |
 pub fn public_dir() -> Option<PathBuf> {
-	platform::public_dir()
+	get(Dir::Public)
 }
 
 /// Returns the path to the user's template directory.
@@ -640,7 +908,7 @@ pub fn public_dir() -> Option<PathBuf> {
 /// | Android | `None`                  |                                      |
 /// | WASM    | `None`                  |                                      |
 pub fn template_dir() -> Option<PathBuf> {
-	platform::template_dir()
+	get(Dir::Template)
 }
 
 /// Returns the path to the user's video directory.
@@ -657,7 +925,133 @@ pub fn template_dir() -> Option<PathBuf> {
 /// | Android | `None`                |                          |
 /// | WASM    | `None`                |                          |
 pub fn video_dir() -> Option<PathBuf> {
-	platform::video_dir()
+	get(Dir::Video)
+}
+
+// =============================================================================
+// System Search Paths
+// =============================================================================
+
+/// Returns the system-wide data directories, in order of preference.
+///
+/// These are searched after [`data_dir()`] for shared or vendor-supplied data. The returned
+/// value depends on the operating system and contains the entries from the following table,
+/// or is empty.
+///
+/// |Platform | Value                                           | Example                                  |
+/// | ------- | ----------------------------------------------- | ---------------------------------------- |
+/// | Linux   | `$XDG_DATA_DIRS` or /usr/local/share:/usr/share | [/usr/local/share, /usr/share]           |
+/// | macOS   | Local and System domain Application Support     | [/Library/Application Support, ...]      |
+/// | Windows | `{FOLDERID_ProgramData}`                        | [C:\ProgramData]                         |
+/// | iOS     | Local and System domain Application Support     |                                          |
+/// | Android | empty                                           |                                          |
+/// | WASM    | empty                                           |                                          |
+///
+/// On Linux and other Unix systems, relative entries are ignored as required by the XDG
+/// specification, and duplicates are removed.
+pub fn data_dirs() -> Vec<PathBuf> {
+	Resolver::from_process_env().data_dirs()
+}
+
+/// Returns the system-wide config directories, in order of preference.
+///
+/// These are searched after [`config_dir()`] for shared or vendor-supplied configuration. The
+/// returned value depends on the operating system and contains the entries from the following
+/// table, or is empty.
+///
+/// |Platform | Value                                       | Example                                  |
+/// | ------- | ------------------------------------------- | ---------------------------------------- |
+/// | Linux   | `$XDG_CONFIG_DIRS` or /etc/xdg              | [/etc/xdg]                               |
+/// | macOS   | Local and System domain Application Support | [/Library/Application Support, ...]      |
+/// | Windows | `{FOLDERID_ProgramData}`                    | [C:\ProgramData]                         |
+/// | iOS     | Local and System domain Application Support |                                          |
+/// | Android | empty                                       |                                          |
+/// | WASM    | empty                                       |                                          |
+///
+/// On Linux and other Unix systems, relative entries are ignored as required by the XDG
+/// specification, and duplicates are removed.
+pub fn config_dirs() -> Vec<PathBuf> {
+	Resolver::from_process_env().config_dirs()
+}
+
+// =============================================================================
+// File Lookup
+// =============================================================================
+
+/// Returns the search order for a user directory plus its system directories,
+/// highest priority first, with duplicates removed.
+fn search_order(user_dir: Option<PathBuf>, system_dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+	let mut dirs: Vec<PathBuf> = Vec::new();
+	for dir in user_dir.into_iter().chain(system_dirs) {
+		if !dirs.contains(&dir) {
+			dirs.push(dir);
+		}
+	}
+	dirs
+}
+
+/// Returns every existing `path` under `dirs`, in the same order as `dirs`.
+fn find_in(dirs: &[PathBuf], path: &Path) -> Vec<PathBuf> {
+	dirs.iter()
+		.map(|dir| dir.join(path))
+		.filter(|candidate| candidate.exists())
+		.collect()
+}
+
+/// Finds a configuration file, searching [`config_dir()`] and then [`config_dirs()`].
+///
+/// Returns the first existing match, so a file in the user's config directory takes
+/// precedence over one shipped in a system location.
+///
+/// # Example
+///
+/// ```rust
+/// if let Some(path) = sysdirs::find_config_file("my-app/config.toml") {
+///     // Linux: /home/alice/.config/my-app/config.toml, or /etc/xdg/my-app/config.toml
+/// }
+/// ```
+pub fn find_config_file<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
+	let dirs = search_order(config_dir(), config_dirs());
+	find_in(&dirs, path.as_ref()).into_iter().next()
+}
+
+/// Finds a data file, searching [`data_dir()`] and then [`data_dirs()`].
+///
+/// Returns the first existing match, so a file in the user's data directory takes
+/// precedence over one shipped in a system location.
+pub fn find_data_file<P: AsRef<Path>>(path: P) -> Option<PathBuf> {
+	let dirs = search_order(data_dir(), data_dirs());
+	find_in(&dirs, path.as_ref()).into_iter().next()
+}
+
+/// Finds every matching configuration file across [`config_dir()`] and [`config_dirs()`].
+///
+/// The result is ordered for layered merging: lowest priority first, so applying each
+/// file in turn lets the user's own config override system-wide defaults.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut settings = Settings::default();
+/// for path in sysdirs::find_all_config_files("my-app/config.toml") {
+///     settings.merge(&std::fs::read_to_string(path)?);
+/// }
+/// ```
+pub fn find_all_config_files<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
+	let dirs = search_order(config_dir(), config_dirs());
+	let mut found = find_in(&dirs, path.as_ref());
+	found.reverse();
+	found
+}
+
+/// Finds every matching data file across [`data_dir()`] and [`data_dirs()`].
+///
+/// Like [`find_all_config_files()`], the result is ordered lowest priority first.
+pub fn find_all_data_files<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
+	let dirs = search_order(data_dir(), data_dirs());
+	let mut found = find_in(&dirs, path.as_ref());
+	found.reverse();
+	found
 }
 
 // =============================================================================
@@ -680,7 +1074,7 @@ pub fn video_dir() -> Option<PathBuf> {
 /// | Android | files/tmp (after init)       | /data/data/com.example/files/tmp |
 /// | WASM    | `None`                       |                              |
 pub fn temp_dir() -> Option<PathBuf> {
-	platform::temp_dir()
+	get(Dir::Temp)
 }
 
 /// Returns the path to the app's Library directory (Apple platforms only).
@@ -699,7 +1093,119 @@ pub fn temp_dir() -> Option<PathBuf> {
 /// | Android | `None`                       |                              |
 /// | WASM    | `None`                       |                              |
 pub fn library_dir() -> Option<PathBuf> {
-	platform::library_dir()
+	get(Dir::Library)
+}
+
+// =============================================================================
+// Result-returning Lookups
+// =============================================================================
+
+// These mirror the functions above, but say why a directory is missing instead of
+// returning `None`, so error messages can tell users what to fix.
+
+/// Like [`home_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_home_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Home)
+}
+
+/// Like [`cache_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_cache_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Cache)
+}
+
+/// Like [`config_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_config_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Config)
+}
+
+/// Like [`config_local_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_config_local_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::ConfigLocal)
+}
+
+/// Like [`data_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_data_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Data)
+}
+
+/// Like [`data_local_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_data_local_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::DataLocal)
+}
+
+/// Like [`executable_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_executable_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Executable)
+}
+
+/// Like [`preference_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_preference_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Preference)
+}
+
+/// Like [`runtime_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_runtime_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Runtime)
+}
+
+/// Like [`state_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_state_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::State)
+}
+
+/// Like [`audio_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_audio_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Audio)
+}
+
+/// Like [`desktop_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_desktop_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Desktop)
+}
+
+/// Like [`document_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_document_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Document)
+}
+
+/// Like [`download_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_download_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Download)
+}
+
+/// Like [`font_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_font_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Font)
+}
+
+/// Like [`picture_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_picture_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Picture)
+}
+
+/// Like [`public_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_public_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Public)
+}
+
+/// Like [`template_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_template_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Template)
+}
+
+/// Like [`video_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_video_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Video)
+}
+
+/// Like [`temp_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_temp_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Temp)
+}
+
+/// Like [`library_dir()`], but returns a [`DirError`] explaining why the directory is missing.
+pub fn try_library_dir() -> Result<PathBuf, DirError> {
+	try_get(Dir::Library)
 }
 
 // =============================================================================
@@ -735,6 +1241,73 @@ mod tests {
 		assert!(data_dir().is_some());
 	}
 
+	#[test]
+	fn test_get_matches_free_functions() {
+		assert_eq!(get(Dir::Home), home_dir());
+		assert_eq!(get(Dir::Cache), cache_dir());
+		assert_eq!(get(Dir::ConfigLocal), config_local_dir());
+		assert_eq!(get(Dir::Temp), temp_dir());
+		assert_eq!(get(Dir::Library), library_dir());
+	}
+
+	#[test]
+	fn test_all_covers_every_dir() {
+		let dirs: Vec<Dir> = all().map(|(dir, _)| dir).collect();
+		assert_eq!(dirs, Dir::ALL);
+	}
+
+	#[test]
+	fn test_search_order_user_first_and_deduped() {
+		let order = search_order(
+			Some(PathBuf::from("/home/alice/.config")),
+			vec![
+				PathBuf::from("/etc/xdg"),
+				PathBuf::from("/home/alice/.config"),
+				PathBuf::from("/opt/xdg"),
+			],
+		);
+		assert_eq!(
+			order,
+			vec![
+				PathBuf::from("/home/alice/.config"),
+				PathBuf::from("/etc/xdg"),
+				PathBuf::from("/opt/xdg"),
+			]
+		);
+	}
+
+	#[test]
+	fn test_search_order_without_user_dir() {
+		let order = search_order(None, vec![PathBuf::from("/etc/xdg")]);
+		assert_eq!(order, vec![PathBuf::from("/etc/xdg")]);
+	}
+
+	#[test]
+	#[cfg(not(target_arch = "wasm32"))]
+	fn test_find_in_keeps_precedence_order() {
+		let root = std::env::temp_dir().join("sysdirs-test-find-in");
+		let _ = std::fs::remove_dir_all(&root);
+
+		let dirs = vec![root.join("user"), root.join("local"), root.join("system")];
+		for dir in [&dirs[0], &dirs[2]] {
+			std::fs::create_dir_all(dir.join("app")).unwrap();
+			std::fs::write(dir.join("app/config.toml"), "").unwrap();
+		}
+		std::fs::create_dir_all(&dirs[1]).unwrap();
+
+		let found = find_in(&dirs, Path::new("app/config.toml"));
+		assert_eq!(
+			found,
+			vec![
+				root.join("user/app/config.toml"),
+				root.join("system/app/config.toml"),
+			]
+		);
+		assert!(find_in(&dirs, Path::new("app/missing.toml")).is_empty());
+
+		let _ = std::fs::remove_dir_all(&root);
+	}
+
 	#[test]
 	#[cfg(target_os = "android")]
 	fn test_android_init() {
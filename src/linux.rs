@@ -2,176 +2,386 @@
 //!
 //! Uses XDG Base Directory and XDG User Directory specifications.
 
+use crate::error::unsupported;
+use crate::explain::{Found, Source};
+use crate::{DirError, Resolver};
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 
 // =============================================================================
 // Core logic (testable, no env access)
 // =============================================================================
 
-/// Expand tilde in a path string given a home directory.
+/// Turn raw bytes from the environment or a file back into an OS string.
+///
+/// Paths are arbitrary bytes on Unix hosts. Other hosts only get here when
+/// emulating, and there invalid UTF-8 is replaced.
+#[cfg(unix)]
+fn bytes_to_os_string(bytes: &[u8]) -> OsString {
+	use std::os::unix::ffi::OsStrExt;
+	OsStr::from_bytes(bytes).to_os_string()
+}
+
+#[cfg(not(unix))]
+fn bytes_to_os_string(bytes: &[u8]) -> OsString {
+	String::from_utf8_lossy(bytes).into_owned().into()
+}
+
+/// Whether `path` is absolute by Unix rules, whatever the host.
+///
+/// `Path::is_absolute()` follows the host, and on Windows rejects `/home/alice`.
+fn is_unix_absolute(path: &Path) -> bool {
+	path.as_os_str().as_encoded_bytes().starts_with(b"/")
+}
+
+/// Looks up the home directory of the user called `name`, for `~name`.
+pub(crate) type UserHome<'a> = &'a dyn Fn(&[u8]) -> Option<PathBuf>;
+
+/// Expand tilde in a path given a home directory.
 /// This is the testable core - no env var access.
-fn expand_tilde_with_home(path_str: &str, home: Option<&Path>) -> Option<PathBuf> {
-	if let Some(rest) = path_str.strip_prefix("~/") {
-		home.map(|h| h.join(rest))
-	} else if path_str == "~" {
-		home.map(|h| h.to_path_buf())
+///
+/// `~user` is looked up with `user_home`, as a shell would, and is `None` for
+/// unknown users. Works on raw bytes so non-UTF-8 paths are preserved exactly.
+pub(crate) fn expand_tilde_with_home(
+	path: &OsStr,
+	home: Option<&Path>,
+	user_home: UserHome<'_>,
+) -> Option<PathBuf> {
+	let Some(rest) = path.as_encoded_bytes().strip_prefix(b"~") else {
+		return Some(PathBuf::from(path));
+	};
+	let (user, rest) = match rest.iter().position(|&b| b == b'/') {
+		Some(slash) => (&rest[..slash], Some(&rest[slash + 1..])),
+		None => (rest, None),
+	};
+
+	let home = if user.is_empty() {
+		home?.to_path_buf()
 	} else {
-		Some(PathBuf::from(path_str))
-	}
+		user_home(user)?
+	};
+	Some(match rest {
+		Some(rest) => home.join(bytes_to_os_string(rest)),
+		None => home,
+	})
+}
+
+/// Resolve a path from an env value, given a home directory.
+/// This is the testable core - no env var access.
+///
+/// Per the XDG spec, empty values are treated as unset and relative paths are
+/// invalid and must be ignored, so both resolve to `None`.
+pub(crate) fn resolve_env_path(
+	env_value: Option<&OsStr>,
+	home: Option<&Path>,
+	user_home: UserHome<'_>,
+) -> Option<PathBuf> {
+	let val = env_value.filter(|val| !val.is_empty())?;
+	expand_tilde_with_home(val, home, user_home).filter(|path| is_unix_absolute(path))
 }
 
 /// Resolve an XDG directory given an env value, home dir, and default suffix.
 /// This is the testable core - no env var access.
-fn resolve_xdg_dir(
-	env_value: Option<&str>,
+///
+/// `env_var` is only used to record where the path came from.
+pub(crate) fn resolve_xdg_dir(
+	env_var: &str,
+	env_value: Option<&OsStr>,
 	home: Option<&Path>,
+	user_home: UserHome<'_>,
 	default_suffix: &str,
-) -> Option<PathBuf> {
-	match env_value {
-		Some(val) => expand_tilde_with_home(val, home),
-		None => home.map(|h| h.join(default_suffix)),
+) -> Option<Found> {
+	if let Some(path) = resolve_env_path(env_value, home, user_home) {
+		let value = env_value.unwrap_or_default().to_os_string();
+		return Some(Found::new(path, Source::env(env_var, value)));
+	}
+
+	let home = home?;
+	let source = Source::XdgDefault {
+		home: home.to_path_buf(),
+	};
+	Some(Found::new(home.join(default_suffix), source))
+}
+
+/// Look up `key` (e.g. `XDG_MUSIC_DIR`) in the contents of a `user-dirs.dirs` file.
+/// This is the testable core - no file access.
+///
+/// Follows the rules of xdg-user-dirs' own lookup: values must be double-quoted
+/// and either start with `$HOME` or be absolute, backslash escapes the next
+/// character, and the last matching line wins. A value that resolves to the
+/// home directory itself means the directory is disabled.
+///
+/// Returns the path along with the 1-based line it came from.
+fn parse_user_dirs(contents: &[u8], key: &str, home: Option<&Path>) -> Option<(PathBuf, usize)> {
+	let mut result = None;
+
+	for (number, line) in contents.split(|&b| b == b'\n').enumerate() {
+		let line = line.trim_ascii_start();
+		if line.starts_with(b"#") {
+			continue;
+		}
+
+		let Some(rest) = line.strip_prefix(key.as_bytes()) else {
+			continue;
+		};
+		let Some(rest) = rest.trim_ascii_start().strip_prefix(b"=") else {
+			continue;
+		};
+		let Some(rest) = rest.trim_ascii_start().strip_prefix(b"\"") else {
+			continue;
+		};
+
+		let (relative_to_home, rest) = match rest.strip_prefix(b"$HOME") {
+			Some(after) if after.starts_with(b"/") || after.starts_with(b"\"") => (true, after),
+			Some(_) => continue,
+			None if rest.starts_with(b"/") => (false, rest),
+			None => continue,
+		};
+
+		let mut value = Vec::new();
+		let mut bytes = rest.iter();
+		while let Some(&b) = bytes.next() {
+			match b {
+				b'"' => break,
+				b'\\' => value.extend(bytes.next()),
+				b => value.push(b),
+			}
+		}
+
+		let path = if relative_to_home {
+			let start = value.iter().position(|&b| b != b'/').unwrap_or(value.len());
+			home.map(|h| h.join(bytes_to_os_string(&value[start..])))
+		} else {
+			Some(PathBuf::from(bytes_to_os_string(&value)))
+		};
+		result = path.map(|path| (path, number + 1));
+	}
+
+	// Pointing a user dir at $HOME is how xdg-user-dirs marks it as disabled
+	match (result, home) {
+		(Some((path, _)), Some(h)) if path == h => None,
+		(result, _) => result,
 	}
 }
 
-/// Resolve an XDG user directory (no default fallback).
-fn resolve_xdg_user_dir(env_value: Option<&str>, home: Option<&Path>) -> Option<PathBuf> {
-	env_value.and_then(|val| expand_tilde_with_home(val, home))
+/// Resolve a colon-separated XDG search path (e.g. `$XDG_DATA_DIRS`).
+/// This is the testable core - no env var access.
+///
+/// Unset or empty values fall back to `default`. Relative entries are ignored,
+/// as the spec requires, and duplicates are dropped keeping the first occurrence.
+pub(crate) fn resolve_xdg_dirs(env_value: Option<&OsStr>, default: &str) -> Vec<PathBuf> {
+	let value = match env_value {
+		Some(val) if !val.is_empty() => val.as_encoded_bytes(),
+		_ => default.as_bytes(),
+	};
+
+	let mut dirs: Vec<PathBuf> = Vec::new();
+	for entry in value
+		.split(|&b| b == b':')
+		.map(bytes_to_os_string)
+		.map(PathBuf::from)
+	{
+		if is_unix_absolute(&entry) && !dirs.contains(&entry) {
+			dirs.push(entry);
+		}
+	}
+	dirs
 }
 
 // =============================================================================
 // Env var wrappers
 // =============================================================================
 
-fn home() -> Option<PathBuf> {
-	std::env::var_os("HOME").map(PathBuf::from)
+fn home(r: &Resolver) -> Option<PathBuf> {
+	r.unix_home().ok().map(|home| home.path)
 }
 
-fn home_ref() -> Option<PathBuf> {
-	home()
+fn xdg_dirs(r: &Resolver, env_var: &str, default: &str) -> Vec<PathBuf> {
+	let env_value = r.var_os(env_var);
+	resolve_xdg_dirs(env_value.as_deref(), default)
 }
 
-fn xdg_dir(env_var: &str, default_suffix: &str) -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var(env_var).ok();
-	resolve_xdg_dir(env_value.as_deref(), home.as_deref(), default_suffix)
+fn xdg_dir(r: &Resolver, env_var: &str, default_suffix: &str) -> Result<Found, DirError> {
+	let home = home(r);
+	let env_value = r.var_os(env_var);
+	resolve_xdg_dir(
+		env_var,
+		env_value.as_deref(),
+		home.as_deref(),
+		&|name| r.user_home(name),
+		default_suffix,
+	)
+	.ok_or_else(|| DirError::from_env(env_var, env_value, DirError::HomeNotSet))
 }
 
-fn xdg_user_dir(env_var: &str) -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var(env_var).ok();
-	resolve_xdg_user_dir(env_value.as_deref(), home.as_deref())
+/// Resolve a directory that only comes from an env var, such as `$XDG_RUNTIME_DIR`.
+fn env_only_dir(r: &Resolver, env_var: &str) -> Result<Found, DirError> {
+	let home = home(r);
+	let env_value = r.var_os(env_var);
+	match resolve_env_path(env_value.as_deref(), home.as_deref(), &|name| {
+		r.user_home(name)
+	}) {
+		Some(path) => Ok(Found::new(
+			path,
+			Source::env(env_var, env_value.unwrap_or_default()),
+		)),
+		None => Err(DirError::from_env(
+			env_var,
+			env_value,
+			DirError::env_var_not_set(env_var),
+		)),
+	}
+}
+
+fn xdg_user_dir(r: &Resolver, env_var: &str) -> Result<Found, DirError> {
+	let from_env = env_only_dir(r, env_var);
+	if from_env.is_ok() {
+		return from_env;
+	}
+
+	// Env vars are rarely exported, so fall back to the file xdg-user-dirs maintains
+	let home = home(r);
+	let from_file = config_dir(r).ok().and_then(|config| {
+		let file = config.path.join("user-dirs.dirs");
+		let contents = std::fs::read(&file).ok()?;
+		let (path, line) = parse_user_dirs(&contents, env_var, home.as_deref())?;
+		Some(Found::new(path, Source::UserDirsFile { path: file, line }))
+	});
+	from_file.ok_or_else(|| from_env.unwrap_err())
 }
 
 // =============================================================================
 // Directory implementations
 // =============================================================================
 
-pub fn home_dir() -> Option<PathBuf> {
-	home()
+pub fn home_dir(r: &Resolver) -> Result<Found, DirError> {
+	r.unix_home()
+}
+
+pub fn cache_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_CACHE_HOME", ".cache")
+}
+
+pub fn config_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_CONFIG_HOME", ".config")
 }
 
-pub fn cache_dir() -> Option<PathBuf> {
-	xdg_dir("XDG_CACHE_HOME", ".cache")
+pub fn config_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	config_dir(r)
 }
 
-pub fn config_dir() -> Option<PathBuf> {
-	xdg_dir("XDG_CONFIG_HOME", ".config")
+pub fn data_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_DATA_HOME", ".local/share")
 }
 
-pub fn config_local_dir() -> Option<PathBuf> {
-	config_dir()
+pub fn data_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	data_dir(r)
 }
 
-pub fn data_dir() -> Option<PathBuf> {
-	xdg_dir("XDG_DATA_HOME", ".local/share")
+pub fn executable_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_BIN_HOME", ".local/bin")
 }
 
-pub fn data_local_dir() -> Option<PathBuf> {
-	data_dir()
+pub fn preference_dir(r: &Resolver) -> Result<Found, DirError> {
+	config_dir(r)
 }
 
-pub fn executable_dir() -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var("XDG_BIN_HOME").ok();
-	resolve_xdg_dir(env_value.as_deref(), home.as_deref(), ".local/bin")
+pub fn runtime_dir(r: &Resolver) -> Result<Found, DirError> {
+	env_only_dir(r, "XDG_RUNTIME_DIR")
 }
 
-pub fn preference_dir() -> Option<PathBuf> {
-	config_dir()
+pub fn state_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_STATE_HOME", ".local/state")
 }
 
-pub fn runtime_dir() -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var("XDG_RUNTIME_DIR").ok();
-	resolve_xdg_user_dir(env_value.as_deref(), home.as_deref())
+pub fn audio_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_user_dir(r, "XDG_MUSIC_DIR")
 }
 
-pub fn state_dir() -> Option<PathBuf> {
-	xdg_dir("XDG_STATE_HOME", ".local/state")
+pub fn desktop_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_user_dir(r, "XDG_DESKTOP_DIR")
 }
 
-pub fn audio_dir() -> Option<PathBuf> {
-	xdg_user_dir("XDG_MUSIC_DIR")
+pub fn document_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_user_dir(r, "XDG_DOCUMENTS_DIR")
 }
 
-pub fn desktop_dir() -> Option<PathBuf> {
-	xdg_user_dir("XDG_DESKTOP_DIR")
+pub fn download_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_user_dir(r, "XDG_DOWNLOAD_DIR")
 }
 
-pub fn document_dir() -> Option<PathBuf> {
-	xdg_user_dir("XDG_DOCUMENTS_DIR")
+pub fn font_dir(r: &Resolver) -> Result<Found, DirError> {
+	data_dir(r).map(|d| d.join("fonts"))
 }
 
-pub fn download_dir() -> Option<PathBuf> {
-	xdg_user_dir("XDG_DOWNLOAD_DIR")
+pub fn picture_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_user_dir(r, "XDG_PICTURES_DIR")
 }
 
-pub fn font_dir() -> Option<PathBuf> {
-	data_dir().map(|d| d.join("fonts"))
+pub fn public_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_user_dir(r, "XDG_PUBLICSHARE_DIR")
 }
 
-pub fn picture_dir() -> Option<PathBuf> {
-	xdg_user_dir("XDG_PICTURES_DIR")
+pub fn template_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_user_dir(r, "XDG_TEMPLATES_DIR")
 }
 
-pub fn public_dir() -> Option<PathBuf> {
-	xdg_user_dir("XDG_PUBLICSHARE_DIR")
+pub fn video_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_user_dir(r, "XDG_VIDEOS_DIR")
 }
 
-pub fn template_dir() -> Option<PathBuf> {
-	xdg_user_dir("XDG_TEMPLATES_DIR")
+// =============================================================================
+// System search paths
+// =============================================================================
+
+pub fn data_dirs(r: &Resolver) -> Vec<PathBuf> {
+	xdg_dirs(r, "XDG_DATA_DIRS", "/usr/local/share:/usr/share")
 }
 
-pub fn video_dir() -> Option<PathBuf> {
-	xdg_user_dir("XDG_VIDEOS_DIR")
+pub fn config_dirs(r: &Resolver) -> Vec<PathBuf> {
+	xdg_dirs(r, "XDG_CONFIG_DIRS", "/etc/xdg")
 }
 
 // =============================================================================
 // sysdirs extensions
 // =============================================================================
 
-pub fn temp_dir() -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var("TMPDIR").ok();
-	match env_value.as_deref() {
-		Some(val) => expand_tilde_with_home(val, home.as_deref()),
-		None => Some(PathBuf::from("/tmp")),
-	}
+pub fn temp_dir(r: &Resolver) -> Result<Found, DirError> {
+	env_only_dir(r, "TMPDIR")
+		.or_else(|_| Ok(Found::new(PathBuf::from("/tmp"), Source::PlatformDefault)))
 }
 
-pub fn library_dir() -> Option<PathBuf> {
-	None
+pub fn library_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
 // =============================================================================
 // Tests (parallel-safe, no env manipulation)
 // =============================================================================
 
-#[cfg(test)]
+#[cfg(all(test, unix))]
 mod tests {
 	use super::*;
+	use std::os::unix::ffi::{OsStrExt, OsStringExt};
 	use std::path::Path;
 
+	fn no_users(_name: &[u8]) -> Option<PathBuf> {
+		None
+	}
+
+	fn only_alice(name: &[u8]) -> Option<PathBuf> {
+		(name == b"alice").then(|| PathBuf::from("/home/alice"))
+	}
+
+	fn xdg_path(env_value: Option<&OsStr>, home: Option<&Path>, suffix: &str) -> Option<PathBuf> {
+		resolve_xdg_dir("XDG_TEST_HOME", env_value, home, &only_alice, suffix)
+			.map(|found| found.path)
+	}
+
+	fn user_dir_path(contents: &[u8], key: &str, home: Option<&Path>) -> Option<PathBuf> {
+		parse_user_dirs(contents, key, home).map(|(path, _)| path)
+	}
+
 	// -------------------------------------------------------------------------
 	// Tilde expansion tests
 	// -------------------------------------------------------------------------
@@ -179,54 +389,54 @@ mod tests {
 	#[test]
 	fn test_tilde_expansion_basic() {
 		let home = Path::new("/home/testuser");
-		let result = expand_tilde_with_home("~/my-cache", Some(home));
+		let result = expand_tilde_with_home(OsStr::new("~/my-cache"), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/my-cache")));
 	}
 
 	#[test]
 	fn test_tilde_expansion_nested() {
 		let home = Path::new("/home/testuser");
-		let result = expand_tilde_with_home("~/foo/bar/baz", Some(home));
+		let result = expand_tilde_with_home(OsStr::new("~/foo/bar/baz"), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/foo/bar/baz")));
 	}
 
 	#[test]
 	fn test_tilde_only() {
 		let home = Path::new("/home/testuser");
-		let result = expand_tilde_with_home("~", Some(home));
+		let result = expand_tilde_with_home(OsStr::new("~"), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/home/testuser")));
 	}
 
 	#[test]
 	fn test_absolute_path_unchanged() {
 		let home = Path::new("/home/testuser");
-		let result = expand_tilde_with_home("/absolute/path", Some(home));
+		let result = expand_tilde_with_home(OsStr::new("/absolute/path"), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/absolute/path")));
 	}
 
 	#[test]
 	fn test_tilde_in_middle_unchanged() {
 		let home = Path::new("/home/testuser");
-		let result = expand_tilde_with_home("/some/~/path", Some(home));
+		let result = expand_tilde_with_home(OsStr::new("/some/~/path"), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/some/~/path")));
 	}
 
 	#[test]
 	fn test_tilde_expansion_no_home() {
-		let result = expand_tilde_with_home("~/my-cache", None);
+		let result = expand_tilde_with_home(OsStr::new("~/my-cache"), None, &no_users);
 		assert_eq!(result, None);
 	}
 
 	#[test]
 	fn test_tilde_only_no_home() {
-		let result = expand_tilde_with_home("~", None);
+		let result = expand_tilde_with_home(OsStr::new("~"), None, &no_users);
 		assert_eq!(result, None);
 	}
 
 	#[test]
 	fn test_absolute_path_no_home() {
 		// Absolute paths should work even without home
-		let result = expand_tilde_with_home("/absolute/path", None);
+		let result = expand_tilde_with_home(OsStr::new("/absolute/path"), None, &no_users);
 		assert_eq!(result, Some(PathBuf::from("/absolute/path")));
 	}
 
@@ -237,88 +447,466 @@ mod tests {
 	#[test]
 	fn test_xdg_dir_with_env_value() {
 		let home = Path::new("/home/testuser");
-		let result = resolve_xdg_dir(Some("/custom/cache"), Some(home), ".cache");
+		let result = xdg_path(Some(OsStr::new("/custom/cache")), Some(home), ".cache");
 		assert_eq!(result, Some(PathBuf::from("/custom/cache")));
 	}
 
 	#[test]
 	fn test_xdg_dir_with_tilde_env_value() {
 		let home = Path::new("/home/testuser");
-		let result = resolve_xdg_dir(Some("~/my-cache"), Some(home), ".cache");
+		let result = xdg_path(Some(OsStr::new("~/my-cache")), Some(home), ".cache");
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/my-cache")));
 	}
 
 	#[test]
 	fn test_xdg_dir_fallback_to_default() {
 		let home = Path::new("/home/testuser");
-		let result = resolve_xdg_dir(None, Some(home), ".cache");
+		let result = xdg_path(None, Some(home), ".cache");
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/.cache")));
 	}
 
 	#[test]
 	fn test_xdg_dir_no_home_no_env() {
-		let result = resolve_xdg_dir(None, None, ".cache");
+		let result = xdg_path(None, None, ".cache");
 		assert_eq!(result, None);
 	}
 
+	#[test]
+	fn test_xdg_dir_empty_env_value_is_unset() {
+		let home = Path::new("/home/testuser");
+		let result = xdg_path(Some(OsStr::new("")), Some(home), ".cache");
+		assert_eq!(result, Some(PathBuf::from("/home/testuser/.cache")));
+	}
+
+	#[test]
+	fn test_xdg_dir_relative_env_value_ignored() {
+		let home = Path::new("/home/testuser");
+		let result = xdg_path(Some(OsStr::new("relative/dir")), Some(home), ".config");
+		assert_eq!(result, Some(PathBuf::from("/home/testuser/.config")));
+
+		let result = xdg_path(Some(OsStr::new("./cache")), Some(home), ".cache");
+		assert_eq!(result, Some(PathBuf::from("/home/testuser/.cache")));
+	}
+
+	#[test]
+	fn test_xdg_dir_tilde_unknown_user_ignored() {
+		// ~user can't be expanded, so the value is invalid like a relative path
+		let home = Path::new("/home/testuser");
+		let result = xdg_path(Some(OsStr::new("~bob/cache")), Some(home), ".cache");
+		assert_eq!(result, Some(PathBuf::from("/home/testuser/.cache")));
+	}
+
+	#[test]
+	fn test_tilde_user_expansion() {
+		let home = Path::new("/home/testuser");
+		assert_eq!(
+			expand_tilde_with_home(OsStr::new("~alice/cache"), Some(home), &only_alice),
+			Some(PathBuf::from("/home/alice/cache"))
+		);
+		assert_eq!(
+			expand_tilde_with_home(OsStr::new("~alice"), None, &only_alice),
+			Some(PathBuf::from("/home/alice"))
+		);
+		assert_eq!(
+			expand_tilde_with_home(OsStr::new("~alice/cache"), Some(home), &no_users),
+			None
+		);
+	}
+
+	#[test]
+	fn test_xdg_dir_invalid_env_value_no_home() {
+		assert_eq!(xdg_path(Some(OsStr::new("")), None, ".cache"), None);
+		assert_eq!(xdg_path(Some(OsStr::new("relative")), None, ".cache"), None);
+	}
+
+	#[test]
+	fn test_env_path_rejects_empty_and_relative() {
+		let home = Path::new("/home/testuser");
+		assert_eq!(
+			resolve_env_path(Some(OsStr::new("")), Some(home), &no_users),
+			None
+		);
+		assert_eq!(
+			resolve_env_path(Some(OsStr::new("run/user")), Some(home), &no_users),
+			None
+		);
+		assert_eq!(resolve_env_path(None, Some(home), &no_users), None);
+		assert_eq!(
+			resolve_env_path(Some(OsStr::new("/run/user/1000")), Some(home), &no_users),
+			Some(PathBuf::from("/run/user/1000"))
+		);
+	}
+
 	#[test]
 	fn test_xdg_user_dir_with_value() {
 		let home = Path::new("/home/testuser");
-		let result = resolve_xdg_user_dir(Some("/home/testuser/Music"), Some(home));
+		let result = resolve_env_path(
+			Some(OsStr::new("/home/testuser/Music")),
+			Some(home),
+			&no_users,
+		);
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/Music")));
 	}
 
 	#[test]
 	fn test_xdg_user_dir_with_tilde() {
 		let home = Path::new("/home/testuser");
-		let result = resolve_xdg_user_dir(Some("~/Music"), Some(home));
+		let result = resolve_env_path(Some(OsStr::new("~/Music")), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/Music")));
 	}
 
+	#[test]
+	fn test_xdg_user_dir_empty_or_relative() {
+		let home = Path::new("/home/testuser");
+		assert_eq!(
+			resolve_env_path(Some(OsStr::new("")), Some(home), &no_users),
+			None
+		);
+		assert_eq!(
+			resolve_env_path(Some(OsStr::new("Music")), Some(home), &no_users),
+			None
+		);
+	}
+
 	#[test]
 	fn test_xdg_user_dir_no_value() {
 		let home = Path::new("/home/testuser");
 		// User dirs have no default - should return None
-		let result = resolve_xdg_user_dir(None, Some(home));
+		let result = resolve_env_path(None, Some(home), &no_users);
+		assert_eq!(result, None);
+	}
+
+	// -------------------------------------------------------------------------
+	// XDG search path tests
+	// -------------------------------------------------------------------------
+
+	#[test]
+	fn test_xdg_dirs_default_when_unset() {
+		let result = resolve_xdg_dirs(None, "/usr/local/share:/usr/share");
+		assert_eq!(
+			result,
+			vec![
+				PathBuf::from("/usr/local/share"),
+				PathBuf::from("/usr/share")
+			]
+		);
+	}
+
+	#[test]
+	fn test_xdg_dirs_default_when_empty() {
+		let result = resolve_xdg_dirs(Some(OsStr::new("")), "/etc/xdg");
+		assert_eq!(result, vec![PathBuf::from("/etc/xdg")]);
+	}
+
+	#[test]
+	fn test_xdg_dirs_preserves_order() {
+		let result = resolve_xdg_dirs(
+			Some(OsStr::new("/opt/share:/usr/share:/srv/share")),
+			"/etc/xdg",
+		);
+		assert_eq!(
+			result,
+			vec![
+				PathBuf::from("/opt/share"),
+				PathBuf::from("/usr/share"),
+				PathBuf::from("/srv/share")
+			]
+		);
+	}
+
+	#[test]
+	fn test_xdg_dirs_drops_relative_and_empty_entries() {
+		let result = resolve_xdg_dirs(
+			Some(OsStr::new("relative/dir::/usr/share:~/share")),
+			"/etc/xdg",
+		);
+		assert_eq!(result, vec![PathBuf::from("/usr/share")]);
+	}
+
+	#[test]
+	fn test_xdg_dirs_dedupes() {
+		let result = resolve_xdg_dirs(
+			Some(OsStr::new("/usr/share:/opt/share:/usr/share/")),
+			"/etc/xdg",
+		);
+		assert_eq!(
+			result,
+			vec![PathBuf::from("/usr/share"), PathBuf::from("/opt/share")]
+		);
+	}
+
+	#[test]
+	fn test_xdg_dirs_all_relative_yields_empty() {
+		let result = resolve_xdg_dirs(Some(OsStr::new("a:b")), "/etc/xdg");
+		assert!(result.is_empty());
+	}
+
+	// -------------------------------------------------------------------------
+	// user-dirs.dirs parsing tests
+	// -------------------------------------------------------------------------
+
+	const USER_DIRS: &[u8] = br#"# This file is written by xdg-user-dirs-update
+# If you want to change or add directories, just edit the line you're
+# interested in. All local changes will be retained on the next run.
+# Format is XDG_xxx_DIR="$HOME/yyy", where yyy is a shell-escaped
+# homedir-relative path, or XDG_xxx_DIR="/yyy", where /yyy is an
+# absolute path. No other format is supported.
+#
+XDG_DESKTOP_DIR="$HOME/Desktop"
+XDG_DOWNLOAD_DIR="$HOME/Downloads"
+XDG_TEMPLATES_DIR="$HOME/"
+XDG_PUBLICSHARE_DIR="$HOME"
+XDG_DOCUMENTS_DIR="/mnt/shared/Documents"
+XDG_MUSIC_DIR="$HOME/My \"Music\""
+XDG_PICTURES_DIR="$HOME/Pictures"
+XDG_VIDEOS_DIR="$HOME/Videos"
+"#;
+
+	#[test]
+	fn test_user_dirs_home_relative() {
+		let home = Path::new("/home/alice");
+		let result = user_dir_path(USER_DIRS, "XDG_DESKTOP_DIR", Some(home));
+		assert_eq!(result, Some(PathBuf::from("/home/alice/Desktop")));
+	}
+
+	#[test]
+	fn test_user_dirs_absolute() {
+		let home = Path::new("/home/alice");
+		let result = user_dir_path(USER_DIRS, "XDG_DOCUMENTS_DIR", Some(home));
+		assert_eq!(result, Some(PathBuf::from("/mnt/shared/Documents")));
+	}
+
+	#[test]
+	fn test_user_dirs_escaped_quotes() {
+		let home = Path::new("/home/alice");
+		let result = user_dir_path(USER_DIRS, "XDG_MUSIC_DIR", Some(home));
+		assert_eq!(result, Some(PathBuf::from("/home/alice/My \"Music\"")));
+	}
+
+	#[test]
+	fn test_user_dirs_home_means_disabled() {
+		let home = Path::new("/home/alice");
+		assert_eq!(
+			user_dir_path(USER_DIRS, "XDG_TEMPLATES_DIR", Some(home)),
+			None
+		);
+		assert_eq!(
+			user_dir_path(USER_DIRS, "XDG_PUBLICSHARE_DIR", Some(home)),
+			None
+		);
+	}
+
+	#[test]
+	fn test_user_dirs_missing_key() {
+		let home = Path::new("/home/alice");
+		let result = user_dir_path(
+			b"XDG_DESKTOP_DIR=\"$HOME/Desktop\"\n",
+			"XDG_MUSIC_DIR",
+			Some(home),
+		);
 		assert_eq!(result, None);
 	}
 
+	#[test]
+	fn test_user_dirs_comments_ignored() {
+		let home = Path::new("/home/alice");
+		let contents = b"# XDG_MUSIC_DIR=\"$HOME/Commented\"\n";
+		assert_eq!(user_dir_path(contents, "XDG_MUSIC_DIR", Some(home)), None);
+	}
+
+	#[test]
+	fn test_user_dirs_last_line_wins() {
+		let home = Path::new("/home/alice");
+		let contents = b"XDG_MUSIC_DIR=\"$HOME/Old\"\nXDG_MUSIC_DIR=\"$HOME/New\"\n";
+		let result = user_dir_path(contents, "XDG_MUSIC_DIR", Some(home));
+		assert_eq!(result, Some(PathBuf::from("/home/alice/New")));
+	}
+
+	#[test]
+	fn test_user_dirs_reports_line() {
+		let home = Path::new("/home/alice");
+		let contents = b"# comment\nXDG_MUSIC_DIR=\"$HOME/Old\"\n\nXDG_MUSIC_DIR=\"$HOME/New\"\n";
+		let result = parse_user_dirs(contents, "XDG_MUSIC_DIR", Some(home));
+		assert_eq!(result, Some((PathBuf::from("/home/alice/New"), 4)));
+	}
+
+	#[test]
+	fn test_user_dirs_whitespace_around_equals() {
+		let home = Path::new("/home/alice");
+		let contents = b"  XDG_MUSIC_DIR = \"$HOME/Music\"\n";
+		let result = user_dir_path(contents, "XDG_MUSIC_DIR", Some(home));
+		assert_eq!(result, Some(PathBuf::from("/home/alice/Music")));
+	}
+
+	#[test]
+	fn test_user_dirs_unsupported_formats_skipped() {
+		let home = Path::new("/home/alice");
+		// Unquoted, relative and other variables are not part of the format
+		let contents = b"XDG_MUSIC_DIR=$HOME/Music\nXDG_VIDEOS_DIR=\"Videos\"\nXDG_DESKTOP_DIR=\"$HOMEDIR/Desktop\"\n";
+		assert_eq!(user_dir_path(contents, "XDG_MUSIC_DIR", Some(home)), None);
+		assert_eq!(user_dir_path(contents, "XDG_VIDEOS_DIR", Some(home)), None);
+		assert_eq!(user_dir_path(contents, "XDG_DESKTOP_DIR", Some(home)), None);
+	}
+
+	#[test]
+	fn test_user_dirs_prefix_key_not_matched() {
+		let home = Path::new("/home/alice");
+		let contents = b"XDG_MUSIC_DIRS=\"$HOME/Music\"\n";
+		assert_eq!(user_dir_path(contents, "XDG_MUSIC_DIR", Some(home)), None);
+	}
+
+	#[test]
+	fn test_user_dirs_no_home() {
+		assert_eq!(user_dir_path(USER_DIRS, "XDG_DESKTOP_DIR", None), None);
+		assert_eq!(
+			user_dir_path(USER_DIRS, "XDG_DOCUMENTS_DIR", None),
+			Some(PathBuf::from("/mnt/shared/Documents"))
+		);
+	}
+
+	// -------------------------------------------------------------------------
+	// Non-UTF-8 path tests
+	// -------------------------------------------------------------------------
+
+	// "café" encoded as Latin-1, which is not valid UTF-8
+	const LATIN1_HOME: &[u8] = b"/home/caf\xe9";
+
+	#[test]
+	fn test_xdg_dir_non_utf8_env_value() {
+		let home = Path::new("/home/testuser");
+		let value = OsStr::from_bytes(b"/data/\xff\xfe/cache");
+		let result = xdg_path(Some(value), Some(home), ".cache");
+		assert_eq!(result, Some(PathBuf::from(value)));
+	}
+
+	#[test]
+	fn test_xdg_dir_non_utf8_home_default() {
+		let home = Path::new(OsStr::from_bytes(LATIN1_HOME));
+		let result = xdg_path(None, Some(home), ".cache");
+		assert_eq!(
+			result.map(|p| p.into_os_string().into_vec()),
+			Some(b"/home/caf\xe9/.cache".to_vec())
+		);
+	}
+
+	#[test]
+	fn test_tilde_expansion_non_utf8() {
+		let home = Path::new(OsStr::from_bytes(LATIN1_HOME));
+		let result =
+			expand_tilde_with_home(OsStr::from_bytes(b"~/m\xfcsic"), Some(home), &no_users);
+		assert_eq!(
+			result.map(|p| p.into_os_string().into_vec()),
+			Some(b"/home/caf\xe9/m\xfcsic".to_vec())
+		);
+	}
+
+	#[test]
+	fn test_xdg_dirs_non_utf8_entries() {
+		let value = OsStr::from_bytes(b"/opt/\xe9t\xe9:/usr/share");
+		let result = resolve_xdg_dirs(Some(value), "/usr/local/share:/usr/share");
+		assert_eq!(
+			result,
+			vec![
+				PathBuf::from(OsStr::from_bytes(b"/opt/\xe9t\xe9")),
+				PathBuf::from("/usr/share")
+			]
+		);
+	}
+
+	#[test]
+	fn test_user_dirs_non_utf8_value() {
+		let home = Path::new(OsStr::from_bytes(LATIN1_HOME));
+		let contents =
+			b"XDG_MUSIC_DIR=\"$HOME/Musique \xe9t\xe9\"\nXDG_VIDEOS_DIR=\"/srv/vid\xe9os\"\n";
+		assert_eq!(
+			user_dir_path(contents, "XDG_MUSIC_DIR", Some(home))
+				.map(|p| p.into_os_string().into_vec()),
+			Some(b"/home/caf\xe9/Musique \xe9t\xe9".to_vec())
+		);
+		assert_eq!(
+			user_dir_path(contents, "XDG_VIDEOS_DIR", Some(home))
+				.map(|p| p.into_os_string().into_vec()),
+			Some(b"/srv/vid\xe9os".to_vec())
+		);
+	}
+
+	#[test]
+	fn test_user_dirs_non_utf8_home_disabled() {
+		let home = Path::new(OsStr::from_bytes(LATIN1_HOME));
+		let contents = b"XDG_TEMPLATES_DIR=\"$HOME/\"\n";
+		assert_eq!(
+			user_dir_path(contents, "XDG_TEMPLATES_DIR", Some(home)),
+			None
+		);
+	}
+
 	// -------------------------------------------------------------------------
 	// Default path tests
 	// -------------------------------------------------------------------------
 
+	#[test]
+	fn test_xdg_dir_sources() {
+		let home = Path::new("/home/alice");
+		let found = resolve_xdg_dir(
+			"XDG_CACHE_HOME",
+			Some(OsStr::new("~/c")),
+			Some(home),
+			&no_users,
+			".cache",
+		);
+		assert_eq!(
+			found.map(|found| found.source),
+			Some(Source::env("XDG_CACHE_HOME", "~/c".into()))
+		);
+
+		// A rejected value falls back to the default, which is what gets reported
+		let found = resolve_xdg_dir(
+			"XDG_CACHE_HOME",
+			Some(OsStr::new("c")),
+			Some(home),
+			&no_users,
+			".cache",
+		);
+		assert_eq!(
+			found.map(|found| found.source),
+			Some(Source::XdgDefault {
+				home: home.to_path_buf()
+			})
+		);
+	}
+
 	#[test]
 	fn test_cache_default() {
 		let home = Path::new("/home/alice");
-		let result = resolve_xdg_dir(None, Some(home), ".cache");
+		let result = xdg_path(None, Some(home), ".cache");
 		assert_eq!(result, Some(PathBuf::from("/home/alice/.cache")));
 	}
 
 	#[test]
 	fn test_config_default() {
 		let home = Path::new("/home/alice");
-		let result = resolve_xdg_dir(None, Some(home), ".config");
+		let result = xdg_path(None, Some(home), ".config");
 		assert_eq!(result, Some(PathBuf::from("/home/alice/.config")));
 	}
 
 	#[test]
 	fn test_data_default() {
 		let home = Path::new("/home/alice");
-		let result = resolve_xdg_dir(None, Some(home), ".local/share");
+		let result = xdg_path(None, Some(home), ".local/share");
 		assert_eq!(result, Some(PathBuf::from("/home/alice/.local/share")));
 	}
 
 	#[test]
 	fn test_state_default() {
 		let home = Path::new("/home/alice");
-		let result = resolve_xdg_dir(None, Some(home), ".local/state");
+		let result = xdg_path(None, Some(home), ".local/state");
 		assert_eq!(result, Some(PathBuf::from("/home/alice/.local/state")));
 	}
 
 	#[test]
 	fn test_bin_default() {
 		let home = Path::new("/home/alice");
-		let result = resolve_xdg_dir(None, Some(home), ".local/bin");
+		let result = xdg_path(None, Some(home), ".local/bin");
 		assert_eq!(result, Some(PathBuf::from("/home/alice/.local/bin")));
 	}
 }
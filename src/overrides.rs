@@ -0,0 +1,100 @@
+//! Process-wide directory overrides
+//!
+//! Like `init_android()` on Android, but for any directory kind
+//! on every platform, and resettable.
+
+use crate::Dir;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{PoisonError, RwLock};
+
+static OVERRIDES: RwLock<BTreeMap<Dir, PathBuf>> = RwLock::new(BTreeMap::new());
+
+/// Returns the override for a directory kind, if one is set.
+pub(crate) fn get(dir: Dir) -> Option<PathBuf> {
+	let overrides = OVERRIDES.read().unwrap_or_else(PoisonError::into_inner);
+	overrides.get(&dir).cloned()
+}
+
+fn replace(dir: Dir, path: Option<PathBuf>) -> Option<PathBuf> {
+	let mut overrides = OVERRIDES.write().unwrap_or_else(PoisonError::into_inner);
+	match path {
+		Some(path) => overrides.insert(dir, path),
+		None => overrides.remove(&dir),
+	}
+}
+
+/// Redirects a directory kind to `path` for the whole process.
+///
+/// Every directory function, [`get()`](crate::get), [`AppDirs`](crate::AppDirs) and
+/// [`Resolver::from_process_env()`](crate::Resolver::from_process_env) return `path`
+/// for `dir` until the override is cleared, even in [portable mode](crate::is_portable).
+/// Resolvers built from a custom environment are not affected.
+///
+/// An override replaces exactly one kind: overriding [`Dir::Data`] doesn't move
+/// [`font_dir()`](crate::font_dir), even on Linux where it is normally derived from it.
+///
+/// Returns the previous override, if any.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::PathBuf;
+/// use sysdirs::Dir;
+///
+/// // e.g. from a `--data-dir` flag
+/// sysdirs::set_override(Dir::Data, "/srv/my-app");
+/// assert_eq!(sysdirs::data_dir(), Some(PathBuf::from("/srv/my-app")));
+///
+/// sysdirs::clear_override(Dir::Data);
+/// ```
+pub fn set_override<P: Into<PathBuf>>(dir: Dir, path: P) -> Option<PathBuf> {
+	replace(dir, Some(path.into()))
+}
+
+/// Removes the override for a directory kind, returning it if one was set.
+pub fn clear_override(dir: Dir) -> Option<PathBuf> {
+	replace(dir, None)
+}
+
+/// Removes every override.
+pub fn clear_all_overrides() {
+	let mut overrides = OVERRIDES.write().unwrap_or_else(PoisonError::into_inner);
+	overrides.clear();
+}
+
+/// Overrides a directory kind until the returned guard is dropped.
+///
+/// Dropping the guard restores whatever was in place before, either the previous
+/// override or none.
+///
+/// # Example
+///
+/// ```rust
+/// use std::path::PathBuf;
+/// use sysdirs::Dir;
+///
+/// {
+///     let _guard = sysdirs::scoped_override(Dir::State, "/tmp/test-state");
+///     assert_eq!(sysdirs::state_dir(), Some(PathBuf::from("/tmp/test-state")));
+/// }
+/// assert_ne!(sysdirs::state_dir(), Some(PathBuf::from("/tmp/test-state")));
+/// ```
+pub fn scoped_override<P: Into<PathBuf>>(dir: Dir, path: P) -> OverrideGuard {
+	let previous = set_override(dir, path);
+	OverrideGuard { dir, previous }
+}
+
+/// Restores a directory override when dropped. Returned by [`scoped_override()`].
+#[must_use = "the override is removed as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct OverrideGuard {
+	dir: Dir,
+	previous: Option<PathBuf>,
+}
+
+impl Drop for OverrideGuard {
+	fn drop(&mut self) {
+		replace(self.dir, self.previous.take());
+	}
+}
@@ -0,0 +1,304 @@
+//! The home directory from the password database
+//!
+//! `$HOME` is often unset under systemd services, `env -i` and some CI runners.
+//! Like `dirs-sys`, the Unix backends then fall back to the user's passwd entry,
+//! read with `getpwuid_r()`. The same lookup resolves another user's directories
+//! and `~user` in XDG variables.
+
+use crate::Resolver;
+use std::ffi::{CString, OsString};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+// =============================================================================
+// Core logic (testable, no global state)
+// =============================================================================
+
+/// Turn a passwd entry's `pw_dir` into a home directory, rejecting empty and
+/// relative entries. This is the testable core - no passwd access.
+fn home_from_entry(dir: Vec<u8>) -> Option<PathBuf> {
+	let home = PathBuf::from(bytes_to_os(dir));
+	home.is_absolute().then_some(home)
+}
+
+#[cfg(unix)]
+fn bytes_to_os(bytes: Vec<u8>) -> OsString {
+	use std::os::unix::ffi::OsStringExt;
+	OsString::from_vec(bytes)
+}
+
+#[cfg(not(unix))]
+fn bytes_to_os(bytes: Vec<u8>) -> OsString {
+	OsString::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// =============================================================================
+// Global state
+// =============================================================================
+
+/// Sets whether the Unix backends fall back to the password database when `$HOME`
+/// is unset or empty. On by default.
+///
+/// Whether the fallback was used shows in [`explain(Dir::Home)`](crate::explain)
+/// as [`Source::Passwd`](crate::Source::Passwd). A [`Resolver`](crate::Resolver)
+/// can choose for itself with
+/// [`with_passwd_fallback()`](crate::Resolver::with_passwd_fallback).
+pub fn set_passwd_fallback(enabled: bool) {
+	ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if the password database fallback is on. See [`set_passwd_fallback()`].
+pub fn passwd_fallback_enabled() -> bool {
+	ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the effective user ID, or `None` where there are no user IDs.
+#[cfg(unix)]
+pub(crate) fn euid() -> Option<u32> {
+	Some(crate::ensure::sys::euid())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn euid() -> Option<u32> {
+	None
+}
+
+/// The fields of a `struct passwd` that are used, copied out of the C buffer.
+struct Entry {
+	uid: u32,
+	gid: u32,
+	name: Vec<u8>,
+	dir: Vec<u8>,
+}
+
+/// A user's entry in the password database, with a usable home directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct User {
+	pub uid: u32,
+	pub gid: u32,
+	pub name: OsString,
+	pub home: PathBuf,
+}
+
+impl User {
+	fn from_entry(entry: Entry) -> Option<User> {
+		Some(User {
+			uid: entry.uid,
+			gid: entry.gid,
+			name: bytes_to_os(entry.name),
+			home: home_from_entry(entry.dir)?,
+		})
+	}
+}
+
+/// Returns the entry for `uid` from the password database.
+pub(crate) fn user_by_uid(uid: u32) -> Option<User> {
+	sys::by_uid(uid).and_then(User::from_entry)
+}
+
+/// Returns the entry for the user called `name` from the password database.
+pub(crate) fn user_by_name(name: &[u8]) -> Option<User> {
+	let name = CString::new(name).ok()?;
+	sys::by_name(&name).and_then(User::from_entry)
+}
+
+/// Returns the home directory of `uid` from the password database.
+pub(crate) fn home(uid: u32) -> Option<PathBuf> {
+	user_by_uid(uid).map(|user| user.home)
+}
+
+/// Returns the home directory of the user called `name`, for `~name`.
+pub(crate) fn home_named(name: &[u8]) -> Option<PathBuf> {
+	user_by_name(name).map(|user| user.home)
+}
+
+/// Returns a resolver for the directories of the user called `name`.
+///
+/// The home directory comes from the user's entry in the password database, and
+/// the rest follow from it as they would for that user: the XDG defaults and their
+/// `user-dirs.dirs`. The calling process's environment belongs to a different user,
+/// e.g. root, so none of it is read. Variables the user sets in their own session,
+/// such as `XDG_CONFIG_HOME`, aren't seen either.
+///
+/// Returns `None` if there is no such user, or on platforms without a password
+/// database (Windows, Android, WASM).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// // In admin tooling running as root
+/// if let Some(alice) = sysdirs::for_user("alice") {
+///     let config = alice.config_dir().map(|dir| dir.join("my-app"));
+///     // Lin: Some(/home/alice/.config/my-app)
+/// }
+/// ```
+pub fn for_user(name: &str) -> Option<Resolver> {
+	let user = user_by_name(name.as_bytes())?;
+	Some(Resolver::for_user(user.uid, user.home))
+}
+
+/// Like [`for_user()`], but looks the user up by uid.
+pub fn for_uid(uid: u32) -> Option<Resolver> {
+	Some(Resolver::for_user(uid, home(uid)?))
+}
+
+// `struct passwd` differs between platforms, and only these layouts are declared.
+// 32-bit BSDs are left out as their `time_t` fields vary in size.
+#[cfg(any(
+	all(target_os = "linux", any(target_env = "gnu", target_env = "musl")),
+	all(
+		target_pointer_width = "64",
+		any(target_vendor = "apple", target_os = "freebsd", target_os = "openbsd")
+	)
+))]
+mod sys {
+	use super::Entry;
+	use std::ffi::{CStr, c_char, c_int};
+	use std::mem::MaybeUninit;
+	use std::ptr;
+
+	#[cfg(target_os = "linux")]
+	#[repr(C)]
+	struct Passwd {
+		pw_name: *mut c_char,
+		pw_passwd: *mut c_char,
+		pw_uid: u32,
+		pw_gid: u32,
+		pw_gecos: *mut c_char,
+		pw_dir: *mut c_char,
+		pw_shell: *mut c_char,
+	}
+
+	#[cfg(not(target_os = "linux"))]
+	#[repr(C)]
+	struct Passwd {
+		pw_name: *mut c_char,
+		pw_passwd: *mut c_char,
+		pw_uid: u32,
+		pw_gid: u32,
+		pw_change: i64,
+		pw_class: *mut c_char,
+		pw_gecos: *mut c_char,
+		pw_dir: *mut c_char,
+		pw_shell: *mut c_char,
+		pw_expire: i64,
+		// FreeBSD only, but harmless as spare room elsewhere
+		pw_fields: c_int,
+	}
+
+	unsafe extern "C" {
+		fn getpwuid_r(
+			uid: u32,
+			pwd: *mut Passwd,
+			buf: *mut c_char,
+			buflen: usize,
+			result: *mut *mut Passwd,
+		) -> c_int;
+		fn getpwnam_r(
+			name: *const c_char,
+			pwd: *mut Passwd,
+			buf: *mut c_char,
+			buflen: usize,
+			result: *mut *mut Passwd,
+		) -> c_int;
+	}
+
+	const ERANGE: c_int = 34;
+
+	type GetPw<'a> = dyn FnMut(*mut Passwd, *mut c_char, usize, *mut *mut Passwd) -> c_int + 'a;
+
+	/// Calls a `getpw*_r()` function, growing the string buffer as needed.
+	fn lookup(get: &mut GetPw<'_>) -> Option<Entry> {
+		let mut buf: Vec<c_char> = vec![0; 1024];
+		loop {
+			let mut pwd = MaybeUninit::<Passwd>::zeroed();
+			let mut result = ptr::null_mut();
+			let rc = get(pwd.as_mut_ptr(), buf.as_mut_ptr(), buf.len(), &mut result);
+			if rc == ERANGE && buf.len() < 1 << 20 {
+				buf.resize(buf.len() * 2, 0);
+				continue;
+			}
+			if rc != 0 || result.is_null() {
+				return None;
+			}
+			// SAFETY: on success `result` points to `pwd`, whose strings are null or
+			// NUL-terminated in `buf`
+			let pwd = unsafe { &*result };
+			if pwd.pw_name.is_null() || pwd.pw_dir.is_null() {
+				return None;
+			}
+			let copy = |s| unsafe { CStr::from_ptr(s) }.to_bytes().to_vec();
+			return Some(Entry {
+				uid: pwd.pw_uid,
+				gid: pwd.pw_gid,
+				name: copy(pwd.pw_name),
+				dir: copy(pwd.pw_dir),
+			});
+		}
+	}
+
+	pub fn by_uid(uid: u32) -> Option<Entry> {
+		// SAFETY: lookup() passes pointers valid for the lengths given
+		lookup(&mut |pwd, buf, len, result| unsafe { getpwuid_r(uid, pwd, buf, len, result) })
+	}
+
+	pub fn by_name(name: &CStr) -> Option<Entry> {
+		// SAFETY: lookup() passes pointers valid for the lengths given, and `name` is
+		// NUL-terminated
+		lookup(&mut |pwd, buf, len, result| unsafe {
+			getpwnam_r(name.as_ptr(), pwd, buf, len, result)
+		})
+	}
+}
+
+#[cfg(not(any(
+	all(target_os = "linux", any(target_env = "gnu", target_env = "musl")),
+	all(
+		target_pointer_width = "64",
+		any(target_vendor = "apple", target_os = "freebsd", target_os = "openbsd")
+	)
+)))]
+mod sys {
+	use super::Entry;
+	use std::ffi::CStr;
+
+	pub fn by_uid(_uid: u32) -> Option<Entry> {
+		None
+	}
+
+	pub fn by_name(_name: &CStr) -> Option<Entry> {
+		None
+	}
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_home_from_entry() {
+		assert_eq!(
+			home_from_entry(b"/home/alice".to_vec()),
+			Some(PathBuf::from("/home/alice"))
+		);
+		assert_eq!(home_from_entry(Vec::new()), None);
+		assert_eq!(home_from_entry(b"home/alice".to_vec()), None);
+	}
+
+	#[test]
+	#[cfg(any(target_os = "linux", target_os = "macos"))]
+	fn test_root_has_an_entry() {
+		assert!(home(0).is_some());
+		let root = user_by_name(b"root").unwrap();
+		assert_eq!((root.uid, root.gid), (0, 0));
+		assert_eq!(user_by_uid(0), Some(root));
+		assert_eq!(user_by_name(b"no-such-user-sysdirs"), None);
+		assert_eq!(user_by_name(b"ro\0ot"), None);
+	}
+}
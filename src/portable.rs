@@ -0,0 +1,195 @@
+//! Portable mode
+//!
+//! Keeps an application's directories beside its executable, e.g. for builds that run
+//! from a USB stick.
+
+use crate::Dir;
+use crate::explain::{Found, Source};
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
+
+/// The marker file name used unless [`set_portable_marker()`] changes it.
+pub const DEFAULT_PORTABLE_MARKER: &str = "portable.txt";
+
+/// Whether portable mode is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PortableMode {
+	/// On if the marker file sits next to the executable.
+	#[default]
+	Auto,
+	/// Always on.
+	Enabled,
+	/// Always off, even if the marker file exists.
+	Disabled,
+}
+
+struct State {
+	mode: PortableMode,
+	marker: Option<String>,
+	// Cached result of looking for the executable's directory (and marker)
+	root: Option<Option<PathBuf>>,
+}
+
+static STATE: RwLock<State> = RwLock::new(State {
+	mode: PortableMode::Auto,
+	marker: None,
+	root: None,
+});
+
+// =============================================================================
+// Core logic (testable, no global state)
+// =============================================================================
+
+/// Find the portable root for an executable: its directory, if `marker` is there.
+/// This is the testable core - no global state.
+fn detect(exe: &Path, marker: Option<&str>) -> Option<PathBuf> {
+	let root = exe.parent()?;
+	match marker {
+		Some(marker) if !root.join(marker).is_file() => None,
+		_ => Some(root.to_path_buf()),
+	}
+}
+
+/// Map a directory kind to its folder under the portable root.
+/// This is the testable core - no global state.
+fn resolve(dir: Dir, root: &Path) -> Option<PathBuf> {
+	let subdir = match dir {
+		Dir::Config | Dir::ConfigLocal | Dir::Preference => "config",
+		Dir::Data | Dir::DataLocal => "data",
+		Dir::Cache => "cache",
+		Dir::State => "state",
+		Dir::Temp => "temp",
+		_ => return None,
+	};
+	Some(root.join(subdir))
+}
+
+// =============================================================================
+// Global state
+// =============================================================================
+
+/// Returns the portable path for a directory kind, if portable mode is on and covers it.
+pub(crate) fn get(dir: Dir) -> Option<Found> {
+	let root = portable_dir()?;
+	let path = resolve(dir, &root)?;
+	Some(Found::new(path, Source::Portable { root }))
+}
+
+fn update(f: impl FnOnce(&mut State)) {
+	let mut state = STATE.write().unwrap_or_else(PoisonError::into_inner);
+	f(&mut state);
+	state.root = None;
+}
+
+/// Sets whether portable mode is on.
+///
+/// The default, [`PortableMode::Auto`], turns it on when a marker file (see
+/// [`set_portable_marker()`]) sits next to [`std::env::current_exe()`].
+pub fn set_portable_mode(mode: PortableMode) {
+	update(|state| state.mode = mode);
+}
+
+/// Sets the name of the marker file that turns on portable mode in
+/// [`PortableMode::Auto`]. Defaults to [`DEFAULT_PORTABLE_MARKER`].
+pub fn set_portable_marker<S: Into<String>>(name: S) {
+	update(|state| state.marker = Some(name.into()));
+}
+
+/// Returns the folder holding the portable directories, if portable mode is on.
+///
+/// This is the executable's directory. The marker file is looked for once and
+/// the answer cached until the mode or marker changes.
+pub fn portable_dir() -> Option<PathBuf> {
+	{
+		let state = STATE.read().unwrap_or_else(PoisonError::into_inner);
+		if let Some(root) = &state.root {
+			return root.clone();
+		}
+	}
+
+	let mut state = STATE.write().unwrap_or_else(PoisonError::into_inner);
+	let marker = match state.mode {
+		PortableMode::Disabled => {
+			state.root = Some(None);
+			return None;
+		}
+		PortableMode::Enabled => None,
+		PortableMode::Auto => Some(state.marker.as_deref().unwrap_or(DEFAULT_PORTABLE_MARKER)),
+	};
+
+	let root = std::env::current_exe()
+		.ok()
+		.and_then(|exe| detect(&exe, marker));
+	state.root = Some(root.clone());
+	root
+}
+
+/// Returns `true` if portable mode is on.
+///
+/// # Example
+///
+/// ```rust
+/// if sysdirs::is_portable() {
+///     println!("Running from {:?}", sysdirs::portable_dir());
+/// }
+/// ```
+pub fn is_portable() -> bool {
+	portable_dir().is_some()
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_resolve_subdirs() {
+		let root = Path::new("/media/usb/app");
+		assert_eq!(
+			resolve(Dir::Config, root),
+			Some(PathBuf::from("/media/usb/app/config"))
+		);
+		assert_eq!(
+			resolve(Dir::Preference, root),
+			Some(PathBuf::from("/media/usb/app/config"))
+		);
+		assert_eq!(
+			resolve(Dir::DataLocal, root),
+			Some(PathBuf::from("/media/usb/app/data"))
+		);
+		assert_eq!(
+			resolve(Dir::Cache, root),
+			Some(PathBuf::from("/media/usb/app/cache"))
+		);
+		assert_eq!(
+			resolve(Dir::State, root),
+			Some(PathBuf::from("/media/usb/app/state"))
+		);
+		assert_eq!(
+			resolve(Dir::Temp, root),
+			Some(PathBuf::from("/media/usb/app/temp"))
+		);
+		assert_eq!(resolve(Dir::Home, root), None);
+		assert_eq!(resolve(Dir::Download, root), None);
+	}
+
+	#[test]
+	#[cfg(not(target_arch = "wasm32"))]
+	fn test_detect_marker() {
+		let root = std::env::temp_dir().join("sysdirs-test-portable-detect");
+		let _ = std::fs::remove_dir_all(&root);
+		std::fs::create_dir_all(&root).unwrap();
+		let exe = root.join("app.exe");
+
+		assert_eq!(detect(&exe, Some("portable.txt")), None);
+		assert_eq!(detect(&exe, None), Some(root.clone()));
+
+		std::fs::write(root.join("portable.txt"), "").unwrap();
+		assert_eq!(detect(&exe, Some("portable.txt")), Some(root.clone()));
+
+		let _ = std::fs::remove_dir_all(&root);
+	}
+}
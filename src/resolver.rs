@@ -0,0 +1,564 @@
+//! Injectable environment for directory lookups
+//!
+//! A `Resolver` answers the same questions as the free functions, but reads
+//! environment variables from a caller-supplied source instead of the process
+//! environment. This makes lookups deterministic and safe to test in parallel.
+
+use crate::emulate::Platform;
+use crate::explain::{Found, Resolution, Source};
+use crate::snapshot::{self, Snapshot};
+use crate::{
+	Dir, DirError, Report, android, apple, doctor, linux, overrides, passwd, portable, secure,
+	sudo, unix, wasm, windows,
+};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Calls a function on the backend for the resolver's platform.
+macro_rules! backend {
+	($r:expr, $func:ident) => {
+		match $r.platform {
+			Platform::Linux => linux::$func($r),
+			Platform::Unix => unix::$func($r),
+			Platform::MacOs | Platform::Ios => apple::$func($r),
+			Platform::Windows => windows::$func($r),
+			Platform::Android => android::$func($r),
+			Platform::Wasm => wasm::$func($r),
+		}
+	};
+}
+
+type EnvFn = dyn Fn(&str) -> Option<OsString> + Send + Sync;
+
+#[derive(Clone)]
+enum Env {
+	Process,
+	Map(HashMap<String, OsString>),
+	Fn(Arc<EnvFn>),
+}
+
+/// Resolves directories from an injected environment.
+///
+/// The free functions such as [`cache_dir()`](crate::cache_dir) are equivalent to calling
+/// the same method on [`Resolver::from_process_env()`]. Building a resolver from a map or
+/// closure instead lets tests and tools compute directories without touching the process
+/// environment (`std::env::set_var` is `unsafe` in edition 2024 and racy across threads).
+///
+/// # Example
+///
+/// ```rust
+/// use sysdirs::Resolver;
+///
+/// let resolver = Resolver::from_env([("XDG_CACHE_HOME", "/tmp/cache")])
+///     .with_home("/home/alice");
+///
+/// resolver.cache_dir();
+/// // Lin: Some(/tmp/cache)
+/// resolver.config_dir();
+/// // Lin: Some(/home/alice/.config)
+/// ```
+#[derive(Clone)]
+pub struct Resolver {
+	env: Env,
+	home: Option<PathBuf>,
+	android_cache: Option<PathBuf>,
+	// `None` follows `set_passwd_fallback()`
+	passwd_fallback: Option<bool>,
+	// Set by `for_user()`, whose home came from the password database
+	user: Option<u32>,
+	platform: Platform,
+}
+
+impl Resolver {
+	/// Creates a resolver that reads the current process environment.
+	///
+	/// This is the resolver behind the free functions, so it also honors the
+	/// process-wide [overrides](crate::set_override), [portable mode](crate::is_portable)
+	/// and [sudo policy](crate::set_sudo_policy).
+	pub fn from_process_env() -> Self {
+		Self {
+			env: Env::Process,
+			home: None,
+			android_cache: None,
+			passwd_fallback: None,
+			user: None,
+			platform: Platform::current(),
+		}
+	}
+
+	/// Creates a resolver from a fixed set of environment variables.
+	///
+	/// Variables not in `vars` are treated as unset. Names are matched exactly, so on
+	/// Windows use the same casing the platform backend looks up (e.g. `APPDATA`).
+	pub fn from_env<I, K, V>(vars: I) -> Self
+	where
+		I: IntoIterator<Item = (K, V)>,
+		K: Into<String>,
+		V: Into<OsString>,
+	{
+		let map = vars
+			.into_iter()
+			.map(|(key, value)| (key.into(), value.into()))
+			.collect();
+		Self {
+			env: Env::Map(map),
+			home: None,
+			android_cache: None,
+			passwd_fallback: Some(false),
+			user: None,
+			platform: Platform::current(),
+		}
+	}
+
+	/// Creates a resolver that looks up environment variables with a closure.
+	///
+	/// # Example
+	///
+	/// ```rust
+	/// use sysdirs::Resolver;
+	///
+	/// let resolver = Resolver::from_fn(|key| match key {
+	///     "HOME" => Some("/home/alice".into()),
+	///     _ => None,
+	/// });
+	/// ```
+	pub fn from_fn<F>(lookup: F) -> Self
+	where
+		F: Fn(&str) -> Option<OsString> + Send + Sync + 'static,
+	{
+		Self {
+			env: Env::Fn(Arc::new(lookup)),
+			home: None,
+			android_cache: None,
+			passwd_fallback: Some(false),
+			user: None,
+			platform: Platform::current(),
+		}
+	}
+
+	/// A resolver for another user, whose home is `home`. See [`for_user()`](crate::for_user).
+	pub(crate) fn for_user(uid: u32, home: PathBuf) -> Self {
+		Self {
+			home: Some(home),
+			user: Some(uid),
+			..Self::from_env(Vec::<(String, OsString)>::new())
+		}
+	}
+
+	/// Overrides the home directory.
+	///
+	/// This replaces whatever the platform would otherwise use as home (`$HOME`,
+	/// `%USERPROFILE%`, or the app's files directory on Android), and every directory
+	/// derived from it follows.
+	pub fn with_home<P: Into<PathBuf>>(mut self, home: P) -> Self {
+		self.home = Some(home.into());
+		self.user = None;
+		self
+	}
+
+	/// Supplies the directories that `init_android_with_cache()` would on Android.
+	///
+	/// `files_dir` becomes the home directory, as with [`with_home()`](Self::with_home).
+	/// This is mostly useful together with [`with_platform()`](Self::with_platform).
+	pub fn with_android_dirs<P: Into<PathBuf>, Q: Into<PathBuf>>(
+		mut self,
+		files_dir: P,
+		cache_dir: Q,
+	) -> Self {
+		self.home = Some(files_dir.into());
+		self.android_cache = Some(cache_dir.into());
+		self.user = None;
+		self
+	}
+
+	/// Sets whether the home directory falls back to the current user's entry in the
+	/// password database when `$HOME` is unset or empty. See
+	/// [`set_passwd_fallback()`](crate::set_passwd_fallback).
+	///
+	/// Resolvers from [`from_process_env()`](Self::from_process_env) follow
+	/// `set_passwd_fallback()`, which is on by default. Those from
+	/// [`from_env()`](Self::from_env) and [`from_fn()`](Self::from_fn) leave it off, so
+	/// their answers depend only on the injected environment. The fallback is only
+	/// used on Unix hosts, and only when resolving for the host platform. The same
+	/// setting decides whether `~user` in XDG variables is looked up.
+	pub fn with_passwd_fallback(mut self, enabled: bool) -> Self {
+		self.passwd_fallback = Some(enabled);
+		self
+	}
+
+	/// Resolves directories the way `platform` would, instead of the host platform.
+	///
+	/// See the [`emulate`](crate::emulate) module for what each platform reads.
+	pub fn with_platform(mut self, platform: Platform) -> Self {
+		self.platform = platform;
+		self
+	}
+
+	/// Returns the platform whose conventions this resolver follows.
+	pub fn platform(&self) -> Platform {
+		self.platform
+	}
+
+	/// Returns the value of an environment variable from this resolver's environment.
+	///
+	/// During [secure execution](crate::is_secure_execution), the process environment
+	/// reads as empty for the Unix backends, including macOS and iOS.
+	pub fn var_os(&self, key: &str) -> Option<OsString> {
+		match &self.env {
+			Env::Process if self.ignores_env() => None,
+			Env::Process => std::env::var_os(key),
+			Env::Map(map) => map.get(key).cloned(),
+			Env::Fn(lookup) => lookup(key),
+		}
+	}
+
+	/// Returns the home directory override, if one was set with [`with_home()`](Self::with_home).
+	pub fn home_override(&self) -> Option<&Path> {
+		self.home.as_deref()
+	}
+
+	/// Returns the Android cache directory set with [`with_android_dirs()`](Self::with_android_dirs).
+	pub(crate) fn android_cache_override(&self) -> Option<&Path> {
+		self.android_cache.as_deref()
+	}
+
+	/// Returns the home directory override, falling back to the env var `var`.
+	pub(crate) fn home_from(&self, var: &str) -> Result<Found, DirError> {
+		if let Some(home) = self.home_override() {
+			let source = match self.user {
+				Some(uid) => Source::Passwd { uid },
+				None => Source::Injected,
+			};
+			return Ok(Found::new(home.to_path_buf(), source));
+		}
+		match self.var_os(var) {
+			Some(value) if !value.is_empty() => {
+				Ok(Found::new(PathBuf::from(&value), Source::env(var, value)))
+			}
+			_ => Err(DirError::HomeNotSet),
+		}
+	}
+
+	/// Whether this resolver reads an untrusted process environment for a Unix
+	/// backend, and so ignores it. See [`is_secure_execution()`](crate::is_secure_execution).
+	pub(crate) fn ignores_env(&self) -> bool {
+		let unix = matches!(
+			self.platform,
+			Platform::Linux | Platform::Unix | Platform::MacOs | Platform::Ios
+		);
+		unix && matches!(self.env, Env::Process) && secure::is_secure_execution()
+	}
+
+	/// Returns [`home_from("HOME")`](Self::home_from), falling back to the password
+	/// database if [enabled](Self::with_passwd_fallback). When the environment is
+	/// [ignored](Self::ignores_env), the password database is always used.
+	pub(crate) fn unix_home(&self) -> Result<Found, DirError> {
+		let err = match self.home_from("HOME") {
+			Ok(found) => return Ok(found),
+			Err(err) => err,
+		};
+		if self.reads_passwd()
+			&& let Some(uid) = passwd::euid()
+			&& let Some(home) = passwd::home(uid)
+		{
+			return Ok(Found::new(home, Source::Passwd { uid }));
+		}
+		Err(err)
+	}
+
+	/// Returns the home directory of the user called `name`, for `~name` in XDG
+	/// variables. Looked up under the same conditions as the fallback in
+	/// [`unix_home()`](Self::unix_home).
+	pub(crate) fn user_home(&self, name: &[u8]) -> Option<PathBuf> {
+		if self.reads_passwd() {
+			passwd::home_named(name)
+		} else {
+			None
+		}
+	}
+
+	/// Whether the host's password database may be consulted: the
+	/// [fallback](Self::with_passwd_fallback) is on, or the environment is
+	/// [ignored](Self::ignores_env), and this resolves for the host platform.
+	fn reads_passwd(&self) -> bool {
+		let enabled = self.ignores_env()
+			|| self
+				.passwd_fallback
+				.unwrap_or_else(passwd::passwd_fallback_enabled);
+		enabled && self.platform == Platform::current()
+	}
+
+	/// Returns the env var `var` as a path.
+	pub(crate) fn env_path(&self, var: &str) -> Result<Found, DirError> {
+		match self.var_os(var) {
+			Some(value) => Ok(Found::new(PathBuf::from(&value), Source::env(var, value))),
+			None => Err(DirError::env_var_not_set(var)),
+		}
+	}
+
+	/// Returns the [frozen](crate::freeze) snapshot if this resolver is the one it was
+	/// captured from: the process environment, unmodified.
+	fn frozen(&self) -> Option<&'static Snapshot> {
+		let unmodified = matches!(self.env, Env::Process)
+			&& self.home.is_none()
+			&& self.android_cache.is_none()
+			&& self.passwd_fallback.is_none()
+			&& self.platform == Platform::current();
+		if unmodified { snapshot::frozen() } else { None }
+	}
+
+	/// Returns the path for a directory kind. See [`get()`](crate::get).
+	pub fn get(&self, dir: Dir) -> Option<PathBuf> {
+		self.try_get(dir).ok()
+	}
+
+	/// Returns the path for a directory kind, or why it is missing. See [`try_get()`](crate::try_get).
+	///
+	/// Resolvers reading the process environment return any [override](crate::set_override)
+	/// for `dir` first, then the [portable](crate::portable_dir) directory, then follow
+	/// the [sudo policy](crate::set_sudo_policy).
+	pub fn try_get(&self, dir: Dir) -> Result<PathBuf, DirError> {
+		self.find(dir).map(|found| found.path)
+	}
+
+	/// Returns the path for a directory kind and where it came from. See [`explain()`](crate::explain).
+	pub fn explain(&self, dir: Dir) -> Resolution {
+		Resolution::new(dir, self.find(dir))
+	}
+
+	/// Checks every directory for problems. See [`doctor()`](crate::doctor).
+	pub fn doctor(&self) -> Report {
+		doctor::run(self)
+	}
+
+	fn find(&self, dir: Dir) -> Result<Found, DirError> {
+		if let Env::Process = self.env {
+			if let Some(path) = overrides::get(dir) {
+				return Ok(Found::new(path, Source::Override));
+			}
+			if let Some(snapshot) = self.frozen() {
+				return snapshot.explain(dir).to_found();
+			}
+		}
+		self.find_uncached(dir)
+	}
+
+	/// Like [`find()`](Self::find), but without overrides or the frozen snapshot. This is
+	/// what [`freeze()`](crate::freeze) captures, so overrides keep applying on top.
+	pub(crate) fn find_uncached(&self, dir: Dir) -> Result<Found, DirError> {
+		if let Env::Process = self.env {
+			if let Some(found) = portable::get(dir) {
+				return Ok(found);
+			}
+			if self.home.is_none()
+				&& self.platform == Platform::current()
+				&& let Some(user) = sudo::redirect()
+			{
+				return user.find(dir);
+			}
+		}
+
+		match dir {
+			Dir::Home => backend!(self, home_dir),
+			Dir::Cache => backend!(self, cache_dir),
+			Dir::Config => backend!(self, config_dir),
+			Dir::ConfigLocal => backend!(self, config_local_dir),
+			Dir::Data => backend!(self, data_dir),
+			Dir::DataLocal => backend!(self, data_local_dir),
+			Dir::Executable => backend!(self, executable_dir),
+			Dir::Preference => backend!(self, preference_dir),
+			Dir::Runtime => backend!(self, runtime_dir),
+			Dir::State => backend!(self, state_dir),
+			Dir::Audio => backend!(self, audio_dir),
+			Dir::Desktop => backend!(self, desktop_dir),
+			Dir::Document => backend!(self, document_dir),
+			Dir::Download => backend!(self, download_dir),
+			Dir::Font => backend!(self, font_dir),
+			Dir::Picture => backend!(self, picture_dir),
+			Dir::Public => backend!(self, public_dir),
+			Dir::Template => backend!(self, template_dir),
+			Dir::Video => backend!(self, video_dir),
+			Dir::Temp => backend!(self, temp_dir),
+			Dir::Library => backend!(self, library_dir),
+		}
+	}
+
+	/// Returns an iterator over every directory kind and its path. See [`all()`](crate::all).
+	pub fn all(&self) -> impl Iterator<Item = (Dir, Option<PathBuf>)> + '_ {
+		Dir::ALL.iter().map(move |&dir| (dir, self.get(dir)))
+	}
+
+	/// See [`home_dir()`](crate::home_dir).
+	pub fn home_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Home)
+	}
+
+	/// See [`cache_dir()`](crate::cache_dir).
+	pub fn cache_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Cache)
+	}
+
+	/// See [`config_dir()`](crate::config_dir).
+	pub fn config_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Config)
+	}
+
+	/// See [`config_local_dir()`](crate::config_local_dir).
+	pub fn config_local_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::ConfigLocal)
+	}
+
+	/// See [`data_dir()`](crate::data_dir).
+	pub fn data_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Data)
+	}
+
+	/// See [`data_local_dir()`](crate::data_local_dir).
+	pub fn data_local_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::DataLocal)
+	}
+
+	/// See [`executable_dir()`](crate::executable_dir).
+	pub fn executable_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Executable)
+	}
+
+	/// See [`preference_dir()`](crate::preference_dir).
+	pub fn preference_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Preference)
+	}
+
+	/// See [`runtime_dir()`](crate::runtime_dir).
+	pub fn runtime_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Runtime)
+	}
+
+	/// See [`state_dir()`](crate::state_dir).
+	pub fn state_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::State)
+	}
+
+	/// See [`audio_dir()`](crate::audio_dir).
+	pub fn audio_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Audio)
+	}
+
+	/// See [`desktop_dir()`](crate::desktop_dir).
+	pub fn desktop_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Desktop)
+	}
+
+	/// See [`document_dir()`](crate::document_dir).
+	pub fn document_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Document)
+	}
+
+	/// See [`download_dir()`](crate::download_dir).
+	pub fn download_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Download)
+	}
+
+	/// See [`font_dir()`](crate::font_dir).
+	pub fn font_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Font)
+	}
+
+	/// See [`picture_dir()`](crate::picture_dir).
+	pub fn picture_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Picture)
+	}
+
+	/// See [`public_dir()`](crate::public_dir).
+	pub fn public_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Public)
+	}
+
+	/// See [`template_dir()`](crate::template_dir).
+	pub fn template_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Template)
+	}
+
+	/// See [`video_dir()`](crate::video_dir).
+	pub fn video_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Video)
+	}
+
+	/// See [`temp_dir()`](crate::temp_dir).
+	pub fn temp_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Temp)
+	}
+
+	/// See [`library_dir()`](crate::library_dir).
+	pub fn library_dir(&self) -> Option<PathBuf> {
+		self.get(Dir::Library)
+	}
+
+	/// See [`data_dirs()`](crate::data_dirs).
+	pub fn data_dirs(&self) -> Vec<PathBuf> {
+		match self.frozen() {
+			Some(snapshot) => snapshot.data_dirs().to_vec(),
+			None => backend!(self, data_dirs),
+		}
+	}
+
+	/// See [`config_dirs()`](crate::config_dirs).
+	pub fn config_dirs(&self) -> Vec<PathBuf> {
+		match self.frozen() {
+			Some(snapshot) => snapshot.config_dirs().to_vec(),
+			None => backend!(self, config_dirs),
+		}
+	}
+
+	/// See [`find_config_file()`](crate::find_config_file).
+	pub fn find_config_file<P: AsRef<Path>>(&self, path: P) -> Option<PathBuf> {
+		let dirs = crate::search_order(self.config_dir(), self.config_dirs());
+		crate::find_in(&dirs, path.as_ref()).into_iter().next()
+	}
+
+	/// See [`find_data_file()`](crate::find_data_file).
+	pub fn find_data_file<P: AsRef<Path>>(&self, path: P) -> Option<PathBuf> {
+		let dirs = crate::search_order(self.data_dir(), self.data_dirs());
+		crate::find_in(&dirs, path.as_ref()).into_iter().next()
+	}
+
+	/// See [`find_all_config_files()`](crate::find_all_config_files).
+	pub fn find_all_config_files<P: AsRef<Path>>(&self, path: P) -> Vec<PathBuf> {
+		let dirs = crate::search_order(self.config_dir(), self.config_dirs());
+		let mut found = crate::find_in(&dirs, path.as_ref());
+		found.reverse();
+		found
+	}
+
+	/// See [`find_all_data_files()`](crate::find_all_data_files).
+	pub fn find_all_data_files<P: AsRef<Path>>(&self, path: P) -> Vec<PathBuf> {
+		let dirs = crate::search_order(self.data_dir(), self.data_dirs());
+		let mut found = crate::find_in(&dirs, path.as_ref());
+		found.reverse();
+		found
+	}
+}
+
+impl Default for Resolver {
+	fn default() -> Self {
+		Self::from_process_env()
+	}
+}
+
+impl fmt::Debug for Resolver {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let env: &dyn fmt::Debug = match &self.env {
+			Env::Process => &"process",
+			Env::Map(map) => map,
+			Env::Fn(_) => &"<fn>",
+		};
+		f.debug_struct("Resolver")
+			.field("env", env)
+			.field("home", &self.home)
+			.field("android_cache", &self.android_cache)
+			.field("platform", &self.platform)
+			.finish()
+	}
+}
@@ -0,0 +1,104 @@
+//! A runtime directory even when `$XDG_RUNTIME_DIR` is unset
+//!
+//! cron jobs, `su` sessions, containers and most non-Linux systems run without
+//! `$XDG_RUNTIME_DIR`. Rather than every daemon inventing its own socket location,
+//! fall back to a private per-user directory under the temp directory, as the XDG
+//! base directory spec suggests.
+
+use crate::DirError;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A runtime directory, and whether it is the real one or the fallback.
+///
+/// Returned by [`runtime_dir_or_fallback()`](crate::runtime_dir_or_fallback).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuntimeDir {
+	/// [`runtime_dir()`](crate::runtime_dir), i.e. `$XDG_RUNTIME_DIR`. It is managed by
+	/// the login session and removed when the user logs out.
+	Xdg(PathBuf),
+	/// A private directory under [`temp_dir()`](crate::temp_dir), such as
+	/// `/tmp/runtime-1000`. Nothing cleans it up at logout, and unlike a real runtime
+	/// directory it may survive a reboot if the temp directory isn't cleared.
+	Fallback(PathBuf),
+}
+
+impl RuntimeDir {
+	/// Returns the path.
+	pub fn path(&self) -> &Path {
+		match self {
+			RuntimeDir::Xdg(path) | RuntimeDir::Fallback(path) => path,
+		}
+	}
+
+	/// Returns the path, consuming `self`.
+	pub fn into_path(self) -> PathBuf {
+		match self {
+			RuntimeDir::Xdg(path) | RuntimeDir::Fallback(path) => path,
+		}
+	}
+
+	/// Returns `true` if this is the fallback rather than `$XDG_RUNTIME_DIR`.
+	pub fn is_fallback(&self) -> bool {
+		matches!(self, RuntimeDir::Fallback(_))
+	}
+
+	pub(crate) fn map(self, f: impl FnOnce(PathBuf) -> PathBuf) -> Self {
+		match self {
+			RuntimeDir::Xdg(path) => RuntimeDir::Xdg(f(path)),
+			RuntimeDir::Fallback(path) => RuntimeDir::Fallback(f(path)),
+		}
+	}
+}
+
+/// The fallback's name under the temp directory, unique to the user.
+#[cfg(unix)]
+fn fallback_name() -> String {
+	format!("runtime-{}", crate::ensure::sys::euid())
+}
+
+/// The temp directory is already per-user on the other platforms.
+#[cfg(not(unix))]
+fn fallback_name() -> String {
+	"runtime".to_string()
+}
+
+/// Uses `runtime` if it resolved, otherwise creates or validates the fallback under
+/// `temp`: owned by the current user with mode 0700, and reached without following
+/// symlinks other users could have planted.
+pub(crate) fn resolve(
+	runtime: Result<PathBuf, DirError>,
+	temp: Result<PathBuf, DirError>,
+) -> io::Result<RuntimeDir> {
+	if let Ok(path) = runtime {
+		return Ok(RuntimeDir::Xdg(path));
+	}
+	let temp = temp.map_err(|err| io::Error::new(io::ErrorKind::NotFound, err))?;
+	let path = temp.join(fallback_name());
+	crate::ensure::create_secure(&path, 0o700)?;
+	Ok(RuntimeDir::Fallback(path))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_real_runtime_dir_is_used_as_is() {
+		let dir = resolve(
+			Ok(PathBuf::from("/run/user/1000")),
+			Err(DirError::HomeNotSet),
+		);
+		assert_eq!(
+			dir.unwrap(),
+			RuntimeDir::Xdg(PathBuf::from("/run/user/1000"))
+		);
+	}
+
+	#[test]
+	fn test_no_temp_dir() {
+		let err =
+			resolve(Err(DirError::NotInitialized), Err(DirError::NotInitialized)).unwrap_err();
+		assert_eq!(err.kind(), io::ErrorKind::NotFound);
+	}
+}
@@ -0,0 +1,137 @@
+//! Secure execution
+//!
+//! A setuid, setgid or file-capability program runs with more privileges than the
+//! user who started it, but with that user's environment. If it trusted `$HOME`,
+//! `$XDG_CONFIG_HOME` or `$TMPDIR`, the user could point its writes anywhere. Like
+//! glibc's `secure_getenv()`, the Unix backends then ignore the environment and
+//! fall back to defaults derived from the password database.
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static DETECTED: OnceLock<bool> = OnceLock::new();
+
+// =============================================================================
+// Core logic (testable, no global state)
+// =============================================================================
+
+/// `AT_SECURE` from `<elf.h>`: whether the kernel started the process in secure mode.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const AT_SECURE: usize = 23;
+
+/// Read `AT_SECURE` from the contents of `/proc/self/auxv`, a list of native-word
+/// `(key, value)` pairs ending with `AT_NULL`. This is the testable core - no file access.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn at_secure(auxv: &[u8]) -> Option<bool> {
+	let mut words = auxv
+		.chunks_exact(size_of::<usize>())
+		.map(|word| usize::from_ne_bytes(word.try_into().unwrap()));
+	while let (Some(key), Some(value)) = (words.next(), words.next()) {
+		match key {
+			0 => return None,
+			AT_SECURE => return Some(value != 0),
+			_ => {}
+		}
+	}
+	None
+}
+
+// =============================================================================
+// Global state
+// =============================================================================
+
+/// Sets whether the Unix backends always ignore the process environment, as they
+/// do when [secure execution](is_secure_execution) is detected. Off by default.
+///
+/// Set it before [`freeze()`](crate::freeze), which captures the directories as
+/// they resolve at the time.
+pub fn set_secure_mode(enabled: bool) {
+	ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if the process environment is being ignored: secure mode was
+/// [enabled](set_secure_mode), or the process is setuid, setgid or has file
+/// capabilities.
+///
+/// On Linux this reads `AT_SECURE` from `/proc/self/auxv`, falling back to comparing
+/// the real and effective user and group IDs, which is the check on other Unix
+/// platforms. The answer is worked out once per process.
+///
+/// While this is `true`, resolvers reading the process environment on Linux, macOS
+/// and other Unix platforms see every variable as unset, so `$HOME` comes from the
+/// password database and the rest follow from it. `$TMPDIR` is replaced by `/tmp`.
+/// [`original_user()`](crate::original_user) returns `None`, as
+/// `SUDO_UID` is just another variable. Overrides and resolvers built from an
+/// injected environment are unaffected.
+pub fn is_secure_execution() -> bool {
+	ENABLED.load(Ordering::Relaxed) || *DETECTED.get_or_init(detect)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn detect() -> bool {
+	if let Ok(auxv) = std::fs::read("/proc/self/auxv")
+		&& let Some(secure) = at_secure(&auxv)
+	{
+		return secure;
+	}
+	ids_differ()
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "android"))))]
+fn detect() -> bool {
+	ids_differ()
+}
+
+#[cfg(not(unix))]
+fn detect() -> bool {
+	false
+}
+
+/// Whether the real and effective user or group IDs differ, as in a setuid program.
+#[cfg(unix)]
+fn ids_differ() -> bool {
+	use crate::ensure::sys;
+	sys::uid() != sys::euid() || sys::gid() != sys::egid()
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(all(test, any(target_os = "linux", target_os = "android")))]
+mod tests {
+	use super::*;
+
+	fn auxv(pairs: &[(usize, usize)]) -> Vec<u8> {
+		pairs
+			.iter()
+			.flat_map(|&(key, value)| [key, value])
+			.flat_map(usize::to_ne_bytes)
+			.collect()
+	}
+
+	#[test]
+	fn test_at_secure() {
+		// AT_PAGESZ, then AT_SECURE
+		assert_eq!(at_secure(&auxv(&[(6, 4096), (23, 1), (0, 0)])), Some(true));
+		assert_eq!(at_secure(&auxv(&[(6, 4096), (23, 0), (0, 0)])), Some(false));
+	}
+
+	#[test]
+	fn test_at_secure_missing() {
+		// Entries after AT_NULL don't count
+		assert_eq!(at_secure(&auxv(&[(6, 4096), (0, 0), (23, 1)])), None);
+		assert_eq!(at_secure(&auxv(&[(6, 4096)])), None);
+		assert_eq!(at_secure(&[]), None);
+		// A truncated pair
+		assert_eq!(at_secure(&auxv(&[(23, 1)])[..size_of::<usize>()]), None);
+	}
+
+	#[test]
+	fn test_not_detected_in_tests() {
+		let auxv = std::fs::read("/proc/self/auxv").unwrap();
+		assert_eq!(at_secure(&auxv), Some(false));
+		assert!(!detect());
+	}
+}
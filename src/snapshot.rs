@@ -0,0 +1,131 @@
+//! Every directory, resolved once
+//!
+//! The free functions re-read the environment on every call, and on Android with
+//! `android-auto` each call is a JNI round-trip. A [`Snapshot`] does the work once.
+
+use crate::explain::Resolution;
+use crate::{Dir, DirError, Resolver};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+/// Every directory kind and search path, resolved at one point in time.
+///
+/// Cloning is cheap, as the directories are shared. Two snapshots are equal when
+/// every directory resolved to the same path from the same [`Source`](crate::Source),
+/// so comparing a fresh capture with an old one tells whether anything moved.
+///
+/// # Example
+///
+/// ```rust
+/// use sysdirs::{Dir, Snapshot};
+///
+/// let before = Snapshot::capture();
+/// let cache = before.get(Dir::Cache);
+///
+/// // ...later, e.g. on a config reload
+/// if Snapshot::capture() != before {
+///     println!("directories changed");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+	inner: Arc<Inner>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Inner {
+	dirs: BTreeMap<Dir, Resolution>,
+	data_dirs: Vec<PathBuf>,
+	config_dirs: Vec<PathBuf>,
+}
+
+static FROZEN: OnceLock<Snapshot> = OnceLock::new();
+
+impl Snapshot {
+	/// Resolves every directory from the process environment, as the free functions would.
+	pub fn capture() -> Self {
+		Self::from_resolver(&Resolver::from_process_env())
+	}
+
+	/// Resolves every directory with `resolver`.
+	pub fn from_resolver(resolver: &Resolver) -> Self {
+		Self::resolve(resolver, |dir| resolver.explain(dir))
+	}
+
+	/// Like [`capture()`](Self::capture), but leaves out [overrides](crate::set_override),
+	/// which [`freeze()`] applies on top of the snapshot rather than baking in.
+	fn capture_for_freeze() -> Self {
+		let resolver = Resolver::from_process_env();
+		Self::resolve(&resolver, |dir| {
+			Resolution::new(dir, resolver.find_uncached(dir))
+		})
+	}
+
+	fn resolve(resolver: &Resolver, explain: impl Fn(Dir) -> Resolution) -> Self {
+		let dirs = Dir::ALL.iter().map(|&dir| (dir, explain(dir))).collect();
+		Self {
+			inner: Arc::new(Inner {
+				dirs,
+				data_dirs: resolver.data_dirs(),
+				config_dirs: resolver.config_dirs(),
+			}),
+		}
+	}
+
+	/// Returns the path for a directory kind.
+	pub fn get(&self, dir: Dir) -> Option<&Path> {
+		self.explain(dir).path()
+	}
+
+	/// Returns the path for a directory kind, or why it was missing.
+	pub fn try_get(&self, dir: Dir) -> Result<&Path, &DirError> {
+		self.explain(dir).as_result()
+	}
+
+	/// Returns the path for a directory kind and where it came from.
+	pub fn explain(&self, dir: Dir) -> &Resolution {
+		&self.inner.dirs[&dir]
+	}
+
+	/// Returns an iterator over every directory kind and its path, in [`Dir::ALL`] order.
+	pub fn iter(&self) -> impl Iterator<Item = (Dir, Option<&Path>)> {
+		Dir::ALL.iter().map(|&dir| (dir, self.get(dir)))
+	}
+
+	/// Returns the system data directories. See [`data_dirs()`](crate::data_dirs).
+	pub fn data_dirs(&self) -> &[PathBuf] {
+		&self.inner.data_dirs
+	}
+
+	/// Returns the system config directories. See [`config_dirs()`](crate::config_dirs).
+	pub fn config_dirs(&self) -> &[PathBuf] {
+		&self.inner.config_dirs
+	}
+}
+
+/// Captures a [`Snapshot`] for the rest of the process and serves the free functions
+/// from it.
+///
+/// The first call resolves every directory. After that, the free functions,
+/// [`AppDirs`](crate::AppDirs) and [`Resolver::from_process_env()`] answer from the
+/// snapshot instead of re-reading the environment, so later changes to env vars or
+/// [portable mode](crate::set_portable_mode) are not seen. [Overrides](crate::set_override)
+/// are left out of the snapshot and still take precedence, so one set before freezing
+/// stops applying once cleared. Later calls return the same snapshot.
+///
+/// # Example
+///
+/// ```rust,standalone_crate
+/// // At startup, once the environment is settled
+/// let dirs = sysdirs::freeze();
+/// assert_eq!(sysdirs::home_dir().as_deref(), dirs.get(sysdirs::Dir::Home));
+/// ```
+pub fn freeze() -> &'static Snapshot {
+	FROZEN.get_or_init(Snapshot::capture_for_freeze)
+}
+
+/// Returns the snapshot taken by [`freeze()`], if it has been called.
+pub fn frozen() -> Option<&'static Snapshot> {
+	FROZEN.get()
+}
@@ -0,0 +1,244 @@
+//! The user behind `sudo` and `pkexec`
+//!
+//! `sudo my-tool` runs as root, usually with `$HOME` reset to `/root`, so a tool that
+//! writes a config or cache ends up writing root's. Both `sudo` and `pkexec` leave the
+//! invoking user in the environment (`SUDO_UID`, `SUDO_USER`, `PKEXEC_UID`), which is
+//! enough to resolve that user's directories instead.
+
+use crate::Resolver;
+use crate::passwd;
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::sync::{PoisonError, RwLock};
+
+/// Whose directories the free functions resolve when running under `sudo` or `pkexec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SudoPolicy {
+	/// Resolve root's directories, as for any other process. The default.
+	#[default]
+	Ignore,
+	/// Resolve the directories of the user who ran `sudo` or `pkexec`.
+	OriginalUser,
+}
+
+/// How the process was elevated to root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Elevation {
+	/// `sudo`, which sets `SUDO_UID` and `SUDO_USER`.
+	Sudo,
+	/// `pkexec`, which sets `PKEXEC_UID`.
+	Pkexec,
+}
+
+/// The user who ran `sudo` or `pkexec`. See [`original_user()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OriginalUser {
+	user: passwd::User,
+	via: Elevation,
+}
+
+impl OriginalUser {
+	/// Returns the user's uid.
+	pub fn uid(&self) -> u32 {
+		self.user.uid
+	}
+
+	/// Returns the user's primary group ID.
+	pub fn gid(&self) -> u32 {
+		self.user.gid
+	}
+
+	/// Returns the user's login name.
+	pub fn name(&self) -> &OsStr {
+		&self.user.name
+	}
+
+	/// Returns the user's home directory, from the password database.
+	pub fn home(&self) -> &Path {
+		&self.user.home
+	}
+
+	/// Returns how the process was elevated.
+	pub fn via(&self) -> Elevation {
+		self.via
+	}
+
+	/// Returns a resolver for the user's directories, like [`for_uid()`](crate::for_uid).
+	pub fn resolver(&self) -> Resolver {
+		Resolver::for_user(self.user.uid, self.user.home.clone())
+	}
+}
+
+static POLICY: RwLock<SudoPolicy> = RwLock::new(SudoPolicy::Ignore);
+
+// =============================================================================
+// Core logic (testable, no global state)
+// =============================================================================
+
+/// Who the environment says elevated the process.
+#[derive(Debug, PartialEq, Eq)]
+enum Claim {
+	Uid(u32, Elevation),
+	Name(OsString),
+}
+
+/// Find the invoking user from the variables `sudo` and `pkexec` set. Only a process
+/// running as root is considered elevated, and a claim of root itself is ignored.
+/// This is the testable core - no env var access.
+fn detect<F>(euid: u32, var: F) -> Option<Claim>
+where
+	F: Fn(&str) -> Option<OsString>,
+{
+	if euid != 0 {
+		return None;
+	}
+	let uid = |key| {
+		var(key)?
+			.to_str()?
+			.parse::<u32>()
+			.ok()
+			.filter(|&uid| uid != 0)
+	};
+	if let Some(uid) = uid("SUDO_UID") {
+		return Some(Claim::Uid(uid, Elevation::Sudo));
+	}
+	if let Some(name) = var("SUDO_USER").filter(|name| !name.is_empty() && name != "root") {
+		return Some(Claim::Name(name));
+	}
+	uid("PKEXEC_UID").map(|uid| Claim::Uid(uid, Elevation::Pkexec))
+}
+
+// =============================================================================
+// Global state
+// =============================================================================
+
+/// Sets whose directories the free functions resolve when running under `sudo` or
+/// `pkexec`. Defaults to [`SudoPolicy::Ignore`].
+///
+/// With [`SudoPolicy::OriginalUser`], the free functions, [`AppDirs`](crate::AppDirs)
+/// and [`Resolver::from_process_env()`] answer for the user from [`original_user()`],
+/// as [`for_uid()`](crate::for_uid) would. [Overrides](crate::set_override) and
+/// [portable mode](crate::is_portable) still come first, and
+/// [`freeze()`](crate::freeze) captures whichever policy is set at the time.
+///
+/// Directories created this way are still owned by root; use
+/// [`original_user()`] to hand them over.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use sysdirs::SudoPolicy;
+///
+/// sysdirs::set_sudo_policy(SudoPolicy::OriginalUser);
+/// let config = sysdirs::config_dir();
+/// // Lin, under `sudo` from alice: Some(/home/alice/.config)
+/// ```
+pub fn set_sudo_policy(policy: SudoPolicy) {
+	*POLICY.write().unwrap_or_else(PoisonError::into_inner) = policy;
+}
+
+/// Returns the policy set with [`set_sudo_policy()`].
+pub fn sudo_policy() -> SudoPolicy {
+	*POLICY.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the user who ran `sudo` or `pkexec`, if the process is running as root
+/// because of one of them.
+///
+/// This doesn't depend on [`set_sudo_policy()`]. `SUDO_UID` is preferred over
+/// `SUDO_USER`, then `PKEXEC_UID`; the user must be in the password database.
+/// Returns `None` on platforms without one (Windows, Android, WASM), and during
+/// [secure execution](crate::is_secure_execution).
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[cfg(unix)]
+/// # fn main() -> std::io::Result<()> {
+/// use sysdirs::PathExt;
+///
+/// let dir = sysdirs::config_dir().join("my-app").ensure()?;
+/// if let Some(user) = sysdirs::original_user() {
+///     std::os::unix::fs::chown(&dir, Some(user.uid()), Some(user.gid()))?;
+/// }
+/// # Ok(())
+/// # }
+/// # #[cfg(not(unix))]
+/// # fn main() {}
+/// ```
+pub fn original_user() -> Option<OriginalUser> {
+	// A setuid program's environment comes from whoever ran it
+	if crate::is_secure_execution() {
+		return None;
+	}
+	let euid = passwd::euid()?;
+	let (user, via) = match detect(euid, |key| std::env::var_os(key))? {
+		Claim::Uid(uid, via) => (passwd::user_by_uid(uid)?, via),
+		Claim::Name(name) => (
+			passwd::user_by_name(name.as_encoded_bytes())?,
+			Elevation::Sudo,
+		),
+	};
+	Some(OriginalUser { user, via })
+}
+
+/// Returns the resolver the process environment should be answered from instead,
+/// if the policy asks for the original user and there is one.
+pub(crate) fn redirect() -> Option<Resolver> {
+	if sudo_policy() != SudoPolicy::OriginalUser {
+		return None;
+	}
+	original_user().map(|user| user.resolver())
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+		let vars: Vec<(String, OsString)> = vars
+			.iter()
+			.map(|(key, value)| (key.to_string(), OsString::from(value)))
+			.collect();
+		move |key| {
+			vars.iter()
+				.find(|(k, _)| k == key)
+				.map(|(_, value)| value.clone())
+		}
+	}
+
+	#[test]
+	fn test_detect_sudo() {
+		let vars = env(&[("SUDO_UID", "1000"), ("SUDO_USER", "alice")]);
+		assert_eq!(detect(0, &vars), Some(Claim::Uid(1000, Elevation::Sudo)));
+
+		let vars = env(&[("SUDO_UID", "x"), ("SUDO_USER", "alice")]);
+		assert_eq!(detect(0, &vars), Some(Claim::Name("alice".into())));
+	}
+
+	#[test]
+	fn test_detect_pkexec() {
+		let vars = env(&[("PKEXEC_UID", "1000")]);
+		assert_eq!(detect(0, &vars), Some(Claim::Uid(1000, Elevation::Pkexec)));
+	}
+
+	#[test]
+	fn test_detect_requires_root() {
+		// `sudo -u bob` also sets SUDO_UID, but bob can't write alice's directories
+		let vars = env(&[("SUDO_UID", "1000"), ("PKEXEC_UID", "1000")]);
+		assert_eq!(detect(1001, &vars), None);
+	}
+
+	#[test]
+	fn test_detect_ignores_root_and_garbage() {
+		let vars = env(&[("SUDO_UID", "0"), ("SUDO_USER", "root"), ("PKEXEC_UID", "")]);
+		assert_eq!(detect(0, &vars), None);
+		assert_eq!(detect(0, env(&[("SUDO_USER", "")])), None);
+		assert_eq!(detect(0, env(&[])), None);
+	}
+}
@@ -1,198 +1,267 @@
 //! Unix fallback platform implementation (FreeBSD, etc.)
 //!
-//! Uses XDG conventions similar to Linux.
+//! Uses XDG conventions similar to Linux, and the Linux backend's core logic
+//! for them, but has no XDG user directories.
 
-use std::path::{Path, PathBuf};
+use crate::error::unsupported;
+use crate::explain::{Found, Source};
+use crate::linux::{resolve_env_path, resolve_xdg_dir, resolve_xdg_dirs};
+use crate::{DirError, Resolver};
+use std::path::PathBuf;
 
 // =============================================================================
-// Core logic (testable, no env access)
+// Env var wrappers
 // =============================================================================
 
-/// Expand tilde in a path string given a home directory.
-/// This is the testable core - no env var access.
-fn expand_tilde_with_home(path_str: &str, home: Option<&Path>) -> Option<PathBuf> {
-	if let Some(rest) = path_str.strip_prefix("~/") {
-		home.map(|h| h.join(rest))
-	} else if path_str == "~" {
-		home.map(|h| h.to_path_buf())
-	} else {
-		Some(PathBuf::from(path_str))
-	}
+fn home(r: &Resolver) -> Option<PathBuf> {
+	r.unix_home().ok().map(|home| home.path)
+}
+
+fn xdg_dirs(r: &Resolver, env_var: &str, default: &str) -> Vec<PathBuf> {
+	let env_value = r.var_os(env_var);
+	resolve_xdg_dirs(env_value.as_deref(), default)
+}
+
+fn xdg_dir(r: &Resolver, env_var: &str, default_suffix: &str) -> Result<Found, DirError> {
+	let home = home(r);
+	let env_value = r.var_os(env_var);
+	resolve_xdg_dir(
+		env_var,
+		env_value.as_deref(),
+		home.as_deref(),
+		&|name| r.user_home(name),
+		default_suffix,
+	)
+	.ok_or_else(|| DirError::from_env(env_var, env_value, DirError::HomeNotSet))
 }
 
-/// Resolve an XDG directory given an env value, home dir, and default suffix.
-/// This is the testable core - no env var access.
-fn resolve_xdg_dir(
-	env_value: Option<&str>,
-	home: Option<&Path>,
-	default_suffix: &str,
-) -> Option<PathBuf> {
-	match env_value {
-		Some(val) => expand_tilde_with_home(val, home),
-		None => home.map(|h| h.join(default_suffix)),
+/// Resolve a directory that only comes from an env var, such as `$XDG_RUNTIME_DIR`.
+fn env_only_dir(r: &Resolver, env_var: &str) -> Result<Found, DirError> {
+	let home = home(r);
+	let env_value = r.var_os(env_var);
+	match resolve_env_path(env_value.as_deref(), home.as_deref(), &|name| {
+		r.user_home(name)
+	}) {
+		Some(path) => Ok(Found::new(
+			path,
+			Source::env(env_var, env_value.unwrap_or_default()),
+		)),
+		None => Err(DirError::from_env(
+			env_var,
+			env_value,
+			DirError::env_var_not_set(env_var),
+		)),
 	}
 }
 
 // =============================================================================
-// Env var wrappers
+// Directory implementations
 // =============================================================================
 
-fn home() -> Option<PathBuf> {
-	std::env::var_os("HOME").map(PathBuf::from)
+pub fn home_dir(r: &Resolver) -> Result<Found, DirError> {
+	r.unix_home()
 }
 
-fn home_ref() -> Option<PathBuf> {
-	home()
+pub fn cache_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_CACHE_HOME", ".cache")
 }
 
-fn xdg_dir(env_var: &str, default_suffix: &str) -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var(env_var).ok();
-	resolve_xdg_dir(env_value.as_deref(), home.as_deref(), default_suffix)
+pub fn config_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_CONFIG_HOME", ".config")
 }
 
-// =============================================================================
-// Directory implementations
-// =============================================================================
-
-pub fn home_dir() -> Option<PathBuf> {
-	home()
+pub fn config_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	config_dir(r)
 }
 
-pub fn cache_dir() -> Option<PathBuf> {
-	xdg_dir("XDG_CACHE_HOME", ".cache")
+pub fn data_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_DATA_HOME", ".local/share")
 }
 
-pub fn config_dir() -> Option<PathBuf> {
-	xdg_dir("XDG_CONFIG_HOME", ".config")
+pub fn data_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	data_dir(r)
 }
 
-pub fn config_local_dir() -> Option<PathBuf> {
-	config_dir()
+pub fn executable_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_BIN_HOME", ".local/bin")
 }
 
-pub fn data_dir() -> Option<PathBuf> {
-	xdg_dir("XDG_DATA_HOME", ".local/share")
+pub fn preference_dir(r: &Resolver) -> Result<Found, DirError> {
+	config_dir(r)
 }
 
-pub fn data_local_dir() -> Option<PathBuf> {
-	data_dir()
+pub fn runtime_dir(r: &Resolver) -> Result<Found, DirError> {
+	env_only_dir(r, "XDG_RUNTIME_DIR")
 }
 
-pub fn executable_dir() -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var("XDG_BIN_HOME").ok();
-	resolve_xdg_dir(env_value.as_deref(), home.as_deref(), ".local/bin")
+pub fn state_dir(r: &Resolver) -> Result<Found, DirError> {
+	xdg_dir(r, "XDG_STATE_HOME", ".local/state")
 }
 
-pub fn preference_dir() -> Option<PathBuf> {
-	config_dir()
+pub fn audio_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn runtime_dir() -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var("XDG_RUNTIME_DIR").ok();
-	env_value.and_then(|val| expand_tilde_with_home(&val, home.as_deref()))
+pub fn desktop_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn state_dir() -> Option<PathBuf> {
-	xdg_dir("XDG_STATE_HOME", ".local/state")
+pub fn document_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn audio_dir() -> Option<PathBuf> {
-	None
+pub fn download_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn desktop_dir() -> Option<PathBuf> {
-	None
+pub fn font_dir(r: &Resolver) -> Result<Found, DirError> {
+	data_dir(r).map(|d| d.join("fonts"))
 }
 
-pub fn document_dir() -> Option<PathBuf> {
-	None
+pub fn picture_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn download_dir() -> Option<PathBuf> {
-	None
+pub fn public_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn font_dir() -> Option<PathBuf> {
-	data_dir().map(|d| d.join("fonts"))
+pub fn template_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn picture_dir() -> Option<PathBuf> {
-	None
+pub fn video_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn public_dir() -> Option<PathBuf> {
-	None
-}
+// =============================================================================
+// System search paths
+// =============================================================================
 
-pub fn template_dir() -> Option<PathBuf> {
-	None
+pub fn data_dirs(r: &Resolver) -> Vec<PathBuf> {
+	xdg_dirs(r, "XDG_DATA_DIRS", "/usr/local/share:/usr/share")
 }
 
-pub fn video_dir() -> Option<PathBuf> {
-	None
+pub fn config_dirs(r: &Resolver) -> Vec<PathBuf> {
+	xdg_dirs(r, "XDG_CONFIG_DIRS", "/etc/xdg")
 }
 
 // =============================================================================
 // sysdirs extensions
 // =============================================================================
 
-pub fn temp_dir() -> Option<PathBuf> {
-	let home = home_ref();
-	let env_value = std::env::var("TMPDIR").ok();
-	match env_value.as_deref() {
-		Some(val) => expand_tilde_with_home(val, home.as_deref()),
-		None => Some(PathBuf::from("/tmp")),
-	}
+pub fn temp_dir(r: &Resolver) -> Result<Found, DirError> {
+	env_only_dir(r, "TMPDIR")
+		.or_else(|_| Ok(Found::new(PathBuf::from("/tmp"), Source::PlatformDefault)))
 }
 
-pub fn library_dir() -> Option<PathBuf> {
-	None
+pub fn library_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
 // =============================================================================
 // Tests (parallel-safe, no env manipulation)
 // =============================================================================
 
-#[cfg(test)]
+#[cfg(all(test, unix))]
 mod tests {
 	use super::*;
+	use crate::linux::expand_tilde_with_home;
+	use std::ffi::OsStr;
+	use std::os::unix::ffi::{OsStrExt, OsStringExt};
 	use std::path::Path;
 
+	fn no_users(_name: &[u8]) -> Option<PathBuf> {
+		None
+	}
+
+	fn xdg_path(env_value: Option<&OsStr>, home: Option<&Path>, suffix: &str) -> Option<PathBuf> {
+		resolve_xdg_dir("XDG_TEST_HOME", env_value, home, &no_users, suffix).map(|found| found.path)
+	}
+
 	#[test]
 	fn test_tilde_expansion_basic() {
 		let home = Path::new("/home/testuser");
-		let result = expand_tilde_with_home("~/my-cache", Some(home));
+		let result = expand_tilde_with_home(OsStr::new("~/my-cache"), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/my-cache")));
 	}
 
 	#[test]
 	fn test_tilde_only() {
 		let home = Path::new("/home/testuser");
-		let result = expand_tilde_with_home("~", Some(home));
+		let result = expand_tilde_with_home(OsStr::new("~"), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/home/testuser")));
 	}
 
 	#[test]
 	fn test_absolute_path_unchanged() {
 		let home = Path::new("/home/testuser");
-		let result = expand_tilde_with_home("/absolute/path", Some(home));
+		let result = expand_tilde_with_home(OsStr::new("/absolute/path"), Some(home), &no_users);
 		assert_eq!(result, Some(PathBuf::from("/absolute/path")));
 	}
 
 	#[test]
 	fn test_xdg_dir_fallback() {
 		let home = Path::new("/home/testuser");
-		let result = resolve_xdg_dir(None, Some(home), ".cache");
+		let result = xdg_path(None, Some(home), ".cache");
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/.cache")));
 	}
 
 	#[test]
 	fn test_xdg_dir_with_tilde() {
 		let home = Path::new("/home/testuser");
-		let result = resolve_xdg_dir(Some("~/custom"), Some(home), ".cache");
+		let result = xdg_path(Some(OsStr::new("~/custom")), Some(home), ".cache");
 		assert_eq!(result, Some(PathBuf::from("/home/testuser/custom")));
 	}
+
+	#[test]
+	fn test_xdg_dir_empty_env_value_is_unset() {
+		let home = Path::new("/home/testuser");
+		let result = xdg_path(Some(OsStr::new("")), Some(home), ".cache");
+		assert_eq!(result, Some(PathBuf::from("/home/testuser/.cache")));
+	}
+
+	#[test]
+	fn test_xdg_dir_relative_env_value_ignored() {
+		let home = Path::new("/home/testuser");
+		let result = xdg_path(Some(OsStr::new("relative/dir")), Some(home), ".config");
+		assert_eq!(result, Some(PathBuf::from("/home/testuser/.config")));
+	}
+
+	#[test]
+	fn test_xdg_dir_non_utf8_env_value() {
+		let home = Path::new("/home/testuser");
+		let value = OsStr::from_bytes(b"/data/\xff\xfe/cache");
+		let result = xdg_path(Some(value), Some(home), ".cache");
+		assert_eq!(result, Some(PathBuf::from(value)));
+	}
+
+	#[test]
+	fn test_tilde_expansion_non_utf8() {
+		let home = Path::new(OsStr::from_bytes(b"/home/caf\xe9"));
+		let result =
+			expand_tilde_with_home(OsStr::from_bytes(b"~/m\xfcsic"), Some(home), &no_users);
+		assert_eq!(
+			result.map(|p| p.into_os_string().into_vec()),
+			Some(b"/home/caf\xe9/m\xfcsic".to_vec())
+		);
+	}
+
+	#[test]
+	fn test_xdg_dirs_default() {
+		let result = resolve_xdg_dirs(None, "/usr/local/share:/usr/share");
+		assert_eq!(
+			result,
+			vec![
+				PathBuf::from("/usr/local/share"),
+				PathBuf::from("/usr/share")
+			]
+		);
+	}
+
+	#[test]
+	fn test_xdg_dirs_drops_relative_and_duplicates() {
+		let result = resolve_xdg_dirs(Some(OsStr::new("/etc/xdg:relative:/etc/xdg")), "/etc/xdg");
+		assert_eq!(result, vec![PathBuf::from("/etc/xdg")]);
+	}
 }
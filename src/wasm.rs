@@ -1,93 +1,104 @@
 //! WASM platform implementation
 //!
-//! WASM has no filesystem - all directory functions return None.
+//! WASM has no filesystem - all directory functions return `Unsupported`.
 
+use crate::error::unsupported;
+use crate::explain::Found;
+use crate::{DirError, Resolver};
 use std::path::PathBuf;
 
 // =============================================================================
-// Directory implementations - all return None
+// Directory implementations - all unsupported
 // =============================================================================
 
-pub fn home_dir() -> Option<PathBuf> {
-	None
+pub fn home_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn cache_dir() -> Option<PathBuf> {
-	None
+pub fn cache_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn config_dir() -> Option<PathBuf> {
-	None
+pub fn config_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn config_local_dir() -> Option<PathBuf> {
-	None
+pub fn config_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn data_dir() -> Option<PathBuf> {
-	None
+pub fn data_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn data_local_dir() -> Option<PathBuf> {
-	None
+pub fn data_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn executable_dir() -> Option<PathBuf> {
-	None
+pub fn executable_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn preference_dir() -> Option<PathBuf> {
-	None
+pub fn preference_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn runtime_dir() -> Option<PathBuf> {
-	None
+pub fn runtime_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn state_dir() -> Option<PathBuf> {
-	None
+pub fn state_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn audio_dir() -> Option<PathBuf> {
-	None
+pub fn audio_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn desktop_dir() -> Option<PathBuf> {
-	None
+pub fn desktop_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn document_dir() -> Option<PathBuf> {
-	None
+pub fn document_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn download_dir() -> Option<PathBuf> {
-	None
+pub fn download_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn font_dir() -> Option<PathBuf> {
-	None
+pub fn font_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn picture_dir() -> Option<PathBuf> {
-	None
+pub fn picture_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn public_dir() -> Option<PathBuf> {
-	None
+pub fn public_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn template_dir() -> Option<PathBuf> {
-	None
+pub fn template_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn video_dir() -> Option<PathBuf> {
-	None
+pub fn video_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn temp_dir() -> Option<PathBuf> {
-	None
+pub fn data_dirs(_r: &Resolver) -> Vec<PathBuf> {
+	Vec::new()
 }
 
-pub fn library_dir() -> Option<PathBuf> {
-	None
+pub fn config_dirs(_r: &Resolver) -> Vec<PathBuf> {
+	Vec::new()
+}
+
+pub fn temp_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
+}
+
+pub fn library_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
@@ -3,114 +3,177 @@
 //! TODO: Implement using Known Folders API for full correctness.
 //! Currently uses environment variables as fallback.
 
+use crate::error::unsupported;
+use crate::explain::Found;
+use crate::{DirError, Resolver};
 use std::path::PathBuf;
 
 // =============================================================================
 // Helpers
 // =============================================================================
 
-fn home() -> Option<PathBuf> {
-	std::env::var_os("USERPROFILE").map(PathBuf::from)
+fn home(r: &Resolver) -> Result<Found, DirError> {
+	r.home_from("USERPROFILE")
 }
 
-fn appdata_roaming() -> Option<PathBuf> {
-	std::env::var_os("APPDATA").map(PathBuf::from)
+fn env_dir(r: &Resolver, var: &str) -> Result<Found, DirError> {
+	r.env_path(var)
 }
 
-fn appdata_local() -> Option<PathBuf> {
-	std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+/// Joins with `\` whatever the host, so emulated paths match the real ones.
+fn join(base: PathBuf, rest: &str) -> PathBuf {
+	let mut path = base.into_os_string();
+	let bytes = path.as_encoded_bytes();
+	if !bytes.is_empty() && !bytes.ends_with(b"\\") && !bytes.ends_with(b"/") {
+		path.push("\\");
+	}
+	path.push(rest);
+	PathBuf::from(path)
+}
+
+fn appdata_roaming(r: &Resolver) -> Result<Found, DirError> {
+	env_dir(r, "APPDATA")
+}
+
+fn appdata_local(r: &Resolver) -> Result<Found, DirError> {
+	env_dir(r, "LOCALAPPDATA")
+}
+
+fn program_data(r: &Resolver) -> Result<Found, DirError> {
+	env_dir(r, "ProgramData")
 }
 
 // =============================================================================
 // Directory implementations
 // =============================================================================
 
-pub fn home_dir() -> Option<PathBuf> {
-	home()
+pub fn home_dir(r: &Resolver) -> Result<Found, DirError> {
+	home(r)
+}
+
+pub fn cache_dir(r: &Resolver) -> Result<Found, DirError> {
+	appdata_local(r)
+}
+
+pub fn config_dir(r: &Resolver) -> Result<Found, DirError> {
+	appdata_roaming(r)
 }
 
-pub fn cache_dir() -> Option<PathBuf> {
-	appdata_local()
+pub fn config_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	appdata_local(r)
 }
 
-pub fn config_dir() -> Option<PathBuf> {
-	appdata_roaming()
+pub fn data_dir(r: &Resolver) -> Result<Found, DirError> {
+	appdata_roaming(r)
 }
 
-pub fn config_local_dir() -> Option<PathBuf> {
-	appdata_local()
+pub fn data_local_dir(r: &Resolver) -> Result<Found, DirError> {
+	appdata_local(r)
 }
 
-pub fn data_dir() -> Option<PathBuf> {
-	appdata_roaming()
+pub fn executable_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn data_local_dir() -> Option<PathBuf> {
-	appdata_local()
+pub fn preference_dir(r: &Resolver) -> Result<Found, DirError> {
+	appdata_roaming(r)
 }
 
-pub fn executable_dir() -> Option<PathBuf> {
-	None
+pub fn runtime_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn preference_dir() -> Option<PathBuf> {
-	appdata_roaming()
+pub fn state_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn runtime_dir() -> Option<PathBuf> {
-	None
+pub fn audio_dir(r: &Resolver) -> Result<Found, DirError> {
+	home(r).map(|found| found.map(|h| join(h, "Music")))
 }
 
-pub fn state_dir() -> Option<PathBuf> {
-	None
+pub fn desktop_dir(r: &Resolver) -> Result<Found, DirError> {
+	home(r).map(|found| found.map(|h| join(h, "Desktop")))
 }
 
-pub fn audio_dir() -> Option<PathBuf> {
-	home().map(|h| h.join("Music"))
+pub fn document_dir(r: &Resolver) -> Result<Found, DirError> {
+	home(r).map(|found| found.map(|h| join(h, "Documents")))
 }
 
-pub fn desktop_dir() -> Option<PathBuf> {
-	home().map(|h| h.join("Desktop"))
+pub fn download_dir(r: &Resolver) -> Result<Found, DirError> {
+	home(r).map(|found| found.map(|h| join(h, "Downloads")))
 }
 
-pub fn document_dir() -> Option<PathBuf> {
-	home().map(|h| h.join("Documents"))
+pub fn font_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
 }
 
-pub fn download_dir() -> Option<PathBuf> {
-	home().map(|h| h.join("Downloads"))
+pub fn picture_dir(r: &Resolver) -> Result<Found, DirError> {
+	home(r).map(|found| found.map(|h| join(h, "Pictures")))
 }
 
-pub fn font_dir() -> Option<PathBuf> {
-	None
+pub fn public_dir(r: &Resolver) -> Result<Found, DirError> {
+	env_dir(r, "PUBLIC")
 }
 
-pub fn picture_dir() -> Option<PathBuf> {
-	home().map(|h| h.join("Pictures"))
+pub fn template_dir(r: &Resolver) -> Result<Found, DirError> {
+	appdata_roaming(r).map(|found| found.map(|a| join(a, "Microsoft\\Windows\\Templates")))
 }
 
-pub fn public_dir() -> Option<PathBuf> {
-	std::env::var_os("PUBLIC").map(PathBuf::from)
+pub fn video_dir(r: &Resolver) -> Result<Found, DirError> {
+	home(r).map(|found| found.map(|h| join(h, "Videos")))
 }
 
-pub fn template_dir() -> Option<PathBuf> {
-	appdata_roaming().map(|a| a.join("Microsoft\\Windows\\Templates"))
+// =============================================================================
+// System search paths
+// =============================================================================
+
+pub fn data_dirs(r: &Resolver) -> Vec<PathBuf> {
+	program_data(r)
+		.into_iter()
+		.map(|found| found.path)
+		.collect()
 }
 
-pub fn video_dir() -> Option<PathBuf> {
-	home().map(|h| h.join("Videos"))
+pub fn config_dirs(r: &Resolver) -> Vec<PathBuf> {
+	program_data(r)
+		.into_iter()
+		.map(|found| found.path)
+		.collect()
 }
 
 // =============================================================================
 // sysdirs extensions
 // =============================================================================
 
-pub fn temp_dir() -> Option<PathBuf> {
-	std::env::var_os("TEMP")
-		.or_else(|| std::env::var_os("TMP"))
-		.map(PathBuf::from)
+pub fn temp_dir(r: &Resolver) -> Result<Found, DirError> {
+	env_dir(r, "TEMP").or_else(|err| env_dir(r, "TMP").map_err(|_| err))
 }
 
-pub fn library_dir() -> Option<PathBuf> {
-	None
+pub fn library_dir(r: &Resolver) -> Result<Found, DirError> {
+	unsupported(r)
+}
+
+// =============================================================================
+// Tests
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_join_uses_backslash() {
+		let base = PathBuf::from("C:\\Users\\Alice");
+		assert_eq!(
+			join(base, "Music"),
+			PathBuf::from("C:\\Users\\Alice\\Music")
+		);
+	}
+
+	#[test]
+	fn test_join_trailing_separator() {
+		let base = PathBuf::from("C:\\");
+		assert_eq!(join(base, "Users"), PathBuf::from("C:\\Users"));
+		assert_eq!(join(PathBuf::new(), "Music"), PathBuf::from("Music"));
+	}
 }
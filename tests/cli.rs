@@ -0,0 +1,101 @@
+//! Tests for the `sysdirs` command-line tool.
+//!
+//! The tool runs with a cleared environment, so its output is deterministic.
+
+#![cfg(all(feature = "cli", target_os = "linux"))]
+
+use std::process::{Command, Output};
+
+fn sysdirs(args: &[&str], env: &[(&str, &str)]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_sysdirs"))
+		.args(args)
+		.env_clear()
+		.envs(env.iter().copied())
+		.output()
+		.unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+	std::str::from_utf8(&output.stdout).unwrap()
+}
+
+const ALICE: &[(&str, &str)] = &[("HOME", "/home/alice")];
+
+#[test]
+fn test_one_dir() {
+	let output = sysdirs(&["cache"], ALICE);
+	assert!(output.status.success());
+	assert_eq!(stdout(&output), "/home/alice/.cache\n");
+
+	let output = sysdirs(&["--app", "com.Foo Corp.Bar App", "config"], ALICE);
+	assert_eq!(stdout(&output), "/home/alice/.config/barapp\n");
+}
+
+#[test]
+fn test_exit_codes() {
+	assert_eq!(sysdirs(&["runtime"], ALICE).status.code(), Some(1));
+	assert_eq!(sysdirs(&["library"], ALICE).status.code(), Some(3));
+	assert_eq!(sysdirs(&["nope"], ALICE).status.code(), Some(2));
+	assert_eq!(sysdirs(&["--app", "myapp"], ALICE).status.code(), Some(2));
+	assert_eq!(
+		sysdirs(&["--app", "com.example.app", "home"], ALICE)
+			.status
+			.code(),
+		Some(2)
+	);
+}
+
+#[test]
+fn test_home_falls_back_to_passwd() {
+	// $HOME is unset, so home comes from the password database
+	let output = sysdirs(&["home"], &[]);
+	assert!(output.status.success());
+	assert!(stdout(&output).starts_with('/'));
+}
+
+#[test]
+fn test_table() {
+	let output = sysdirs(&[], ALICE);
+	assert!(output.status.success());
+	let table = stdout(&output);
+	assert!(
+		table.contains("config-local  /home/alice/.config\n"),
+		"{table}"
+	);
+	assert!(
+		table.contains("runtime       (XDG_RUNTIME_DIR is not set)\n"),
+		"{table}"
+	);
+}
+
+#[test]
+fn test_json() {
+	let output = sysdirs(&["--json"], ALICE);
+	let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(json["data"], "/home/alice/.local/share");
+	assert_eq!(json["runtime"], serde_json::Value::Null);
+	assert_eq!(json["config-dirs"], serde_json::json!(["/etc/xdg"]));
+}
+
+#[test]
+fn test_shell() {
+	let output = sysdirs(
+		&["--shell", "--app", "org.example.app", "state"],
+		&[("HOME", "/home/o'brien")],
+	);
+	assert_eq!(
+		stdout(&output),
+		"export SYSDIRS_STATE='/home/o'\\''brien/.local/state/app'\n"
+	);
+}
+
+#[test]
+fn test_doctor() {
+	let output = sysdirs(&["doctor"], &[("HOME", "/nonexistent/sysdirs-cli")]);
+	assert_eq!(output.status.code(), Some(1));
+	assert!(
+		stdout(&output).starts_with("error: home: /nonexistent/sysdirs-cli does not exist\n"),
+		"{}",
+		stdout(&output)
+	);
+}
@@ -0,0 +1,123 @@
+//! Tests for the reasons given by DirError.
+//!
+//! These use emulated platforms, so they run the same on every host.
+
+use std::ffi::OsString;
+use sysdirs::emulate::Platform;
+use sysdirs::{Dir, DirError, Resolver};
+
+fn empty_env() -> Resolver {
+	Resolver::from_env(Vec::<(String, OsString)>::new())
+}
+
+#[test]
+fn test_home_not_set() {
+	for platform in [
+		Platform::Linux,
+		Platform::Unix,
+		Platform::MacOs,
+		Platform::Windows,
+	] {
+		let r = empty_env().with_platform(platform);
+		assert_eq!(
+			r.try_get(Dir::Home),
+			Err(DirError::HomeNotSet),
+			"{platform}"
+		);
+	}
+}
+
+#[test]
+fn test_xdg_invalid_without_home() {
+	let r =
+		Resolver::from_env([("XDG_CACHE_HOME", "relative/cache")]).with_platform(Platform::Linux);
+	assert_eq!(
+		r.try_get(Dir::Cache),
+		Err(DirError::EnvVarInvalid {
+			var: "XDG_CACHE_HOME".to_string(),
+			value: "relative/cache".into(),
+		})
+	);
+
+	let r = empty_env().with_platform(Platform::Linux);
+	assert_eq!(r.try_get(Dir::Cache), Err(DirError::HomeNotSet));
+}
+
+#[test]
+fn test_runtime_dir_not_set_or_invalid() {
+	let r = Resolver::from_env([("HOME", "/home/alice")]).with_platform(Platform::Linux);
+	assert_eq!(
+		r.try_get(Dir::Runtime),
+		Err(DirError::EnvVarNotSet {
+			var: "XDG_RUNTIME_DIR".to_string()
+		})
+	);
+
+	let r = Resolver::from_env([("HOME", "/home/alice"), ("XDG_RUNTIME_DIR", "run")])
+		.with_platform(Platform::Linux);
+	assert!(matches!(
+		r.try_get(Dir::Runtime),
+		Err(DirError::EnvVarInvalid { var, .. }) if var == "XDG_RUNTIME_DIR"
+	));
+}
+
+#[test]
+fn test_windows_env_var_not_set() {
+	let r =
+		Resolver::from_env([("USERPROFILE", r"C:\Users\Alice")]).with_platform(Platform::Windows);
+	assert_eq!(
+		r.try_get(Dir::Config),
+		Err(DirError::EnvVarNotSet {
+			var: "APPDATA".to_string()
+		})
+	);
+	assert_eq!(
+		r.try_get(Dir::Temp),
+		Err(DirError::EnvVarNotSet {
+			var: "TEMP".to_string()
+		})
+	);
+}
+
+#[test]
+#[cfg(not(target_os = "android"))]
+fn test_android_not_initialized() {
+	let r = empty_env().with_platform(Platform::Android);
+	assert_eq!(r.try_get(Dir::Data), Err(DirError::NotInitialized));
+	assert_eq!(
+		r.try_get(Dir::Audio),
+		Err(DirError::Unsupported {
+			platform: Platform::Android
+		})
+	);
+}
+
+#[test]
+fn test_unsupported() {
+	let r = Resolver::from_env([("HOME", "/home/alice")]).with_platform(Platform::Linux);
+	assert_eq!(
+		r.try_get(Dir::Library),
+		Err(DirError::Unsupported {
+			platform: Platform::Linux
+		})
+	);
+
+	let r = empty_env().with_platform(Platform::Wasm);
+	for &dir in Dir::ALL {
+		assert_eq!(
+			r.try_get(dir),
+			Err(DirError::Unsupported {
+				platform: Platform::Wasm
+			})
+		);
+	}
+}
+
+#[test]
+fn test_try_functions_match_options() {
+	assert_eq!(sysdirs::try_cache_dir().ok(), sysdirs::cache_dir());
+	assert_eq!(sysdirs::try_library_dir().ok(), sysdirs::library_dir());
+	for &dir in Dir::ALL {
+		assert_eq!(sysdirs::try_get(dir).ok(), sysdirs::get(dir));
+	}
+}
@@ -0,0 +1,72 @@
+//! Tests for DirSet and its serde encoding.
+
+use sysdirs::emulate::Platform;
+use sysdirs::{DirSet, Resolver, Snapshot};
+
+fn alice() -> Resolver {
+	Resolver::from_env([("HOME", "/home/alice")]).with_platform(Platform::Linux)
+}
+
+#[test]
+fn test_from_resolver_matches_functions() {
+	let resolver = alice();
+	let dirs = DirSet::from_resolver(&resolver);
+	assert_eq!(dirs.home_dir, resolver.home_dir());
+	assert_eq!(dirs.font_dir, resolver.font_dir());
+	assert_eq!(dirs.library_dir, None);
+	assert_eq!(dirs.data_dirs, resolver.data_dirs());
+	assert_eq!(dirs, DirSet::from(&Snapshot::from_resolver(&resolver)));
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+	use super::*;
+	use std::path::PathBuf;
+	use sysdirs::SearchPathDomain;
+
+	#[test]
+	fn test_json_round_trip() {
+		let dirs = DirSet::from_resolver(&alice());
+		let json = serde_json::to_value(&dirs).unwrap();
+		assert_eq!(json["cache_dir"], "/home/alice/.cache");
+		assert_eq!(json["library_dir"], serde_json::Value::Null);
+		assert_eq!(json["config_dirs"], serde_json::json!(["/etc/xdg"]));
+
+		let back: DirSet = serde_json::from_value(json).unwrap();
+		assert_eq!(back, dirs);
+	}
+
+	#[test]
+	#[cfg(unix)]
+	fn test_non_utf8_round_trip() {
+		use std::ffi::OsString;
+		use std::os::unix::ffi::OsStringExt;
+
+		let home = OsString::from_vec(b"/home/caf\xe9".to_vec());
+		let dirs = DirSet::from_resolver(
+			&Resolver::from_env([("HOME", home.clone())]).with_platform(Platform::Linux),
+		);
+		assert_eq!(dirs.home_dir, Some(PathBuf::from(&home)));
+
+		let json = serde_json::to_string(&dirs).unwrap();
+		let back: DirSet = serde_json::from_str(&json).unwrap();
+		assert_eq!(back.home_dir, Some(PathBuf::from(home)));
+		assert_eq!(back, dirs);
+	}
+
+	#[test]
+	fn test_missing_fields_default() {
+		let dirs: DirSet = serde_json::from_str(r#"{"home_dir": "/home/bob"}"#).unwrap();
+		assert_eq!(dirs.home_dir, Some(PathBuf::from("/home/bob")));
+		assert_eq!(dirs.cache_dir, None);
+		assert!(dirs.data_dirs.is_empty());
+	}
+
+	#[test]
+	fn test_search_path_domain() {
+		let json = serde_json::to_string(&SearchPathDomain::Local).unwrap();
+		assert_eq!(json, r#""Local""#);
+		let domain: SearchPathDomain = serde_json::from_str(&json).unwrap();
+		assert_eq!(domain, SearchPathDomain::Local);
+	}
+}
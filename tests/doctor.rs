@@ -0,0 +1,122 @@
+//! Tests for doctor() against real directories.
+//!
+//! Each test builds its own tree under the temp directory and points an injected
+//! resolver at it.
+
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use sysdirs::emulate::Platform;
+use sysdirs::{Dir, Problem, Resolver, Severity};
+
+fn fresh_dir(name: &str) -> PathBuf {
+	let root = std::env::temp_dir().join(format!("sysdirs-test-doctor-{name}"));
+	let _ = fs::remove_dir_all(&root);
+	fs::create_dir_all(&root).unwrap();
+	root
+}
+
+fn problems(resolver: &Resolver, dir: Dir) -> Vec<Problem> {
+	resolver
+		.doctor()
+		.findings()
+		.iter()
+		.filter(|finding| finding.dir() == dir)
+		.map(|finding| finding.problem().clone())
+		.collect()
+}
+
+#[test]
+fn test_missing_home() {
+	let r = Resolver::from_env([("HOME", "/nonexistent/sysdirs-doctor")])
+		.with_platform(Platform::Linux);
+	let report = r.doctor();
+	assert_eq!(report.worst(), Some(Severity::Error));
+	assert!(!report.is_healthy());
+
+	let first = &report.findings()[0];
+	assert_eq!(first.dir(), Dir::Home);
+	assert_eq!(first.problem(), &Problem::Missing);
+	assert_eq!(
+		first.to_string(),
+		"error: home: /nonexistent/sysdirs-doctor does not exist"
+	);
+}
+
+#[test]
+fn test_invalid_xdg_var() {
+	let r = Resolver::from_env([
+		("HOME", "/nonexistent"),
+		("XDG_CACHE_HOME", "relative/cache"),
+	])
+	.with_platform(Platform::Linux);
+	assert!(problems(&r, Dir::Cache).contains(&Problem::InvalidEnvVar {
+		var: "XDG_CACHE_HOME".to_string(),
+		value: "relative/cache".into(),
+	}));
+}
+
+#[test]
+fn test_user_dir_vars_only_checked_on_linux() {
+	let vars = [
+		("HOME", "/nonexistent"),
+		("XDG_MUSIC_DIR", "relative/music"),
+	];
+	let invalid = Problem::InvalidEnvVar {
+		var: "XDG_MUSIC_DIR".to_string(),
+		value: "relative/music".into(),
+	};
+
+	let linux = Resolver::from_env(vars).with_platform(Platform::Linux);
+	assert!(problems(&linux, Dir::Audio).contains(&invalid));
+
+	// The generic Unix backend never reads them
+	let unix = Resolver::from_env(vars).with_platform(Platform::Unix);
+	assert!(!problems(&unix, Dir::Audio).contains(&invalid));
+}
+
+#[test]
+fn test_permissions() {
+	let root = fresh_dir("permissions");
+	let data = root.join("data");
+	let runtime = root.join("runtime");
+	fs::create_dir(&data).unwrap();
+	fs::create_dir(&runtime).unwrap();
+	fs::set_permissions(&data, fs::Permissions::from_mode(0o777)).unwrap();
+	fs::set_permissions(&runtime, fs::Permissions::from_mode(0o755)).unwrap();
+
+	let r = Resolver::from_env([
+		("XDG_DATA_HOME", data.as_os_str()),
+		("XDG_RUNTIME_DIR", runtime.as_os_str()),
+	])
+	.with_home(&root)
+	.with_platform(Platform::Linux);
+
+	assert_eq!(problems(&r, Dir::Home), vec![]);
+	assert_eq!(
+		problems(&r, Dir::Data),
+		vec![Problem::WorldWritable { mode: 0o777 }]
+	);
+	assert_eq!(
+		problems(&r, Dir::Runtime),
+		vec![Problem::RuntimeDirMode { mode: 0o755 }]
+	);
+
+	let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_not_a_directory() {
+	let root = fresh_dir("not-a-dir");
+	let file = root.join("cache");
+	fs::write(&file, "").unwrap();
+
+	let r = Resolver::from_env([("XDG_CACHE_HOME", file.as_os_str())])
+		.with_home(Path::new(&root))
+		.with_platform(Platform::Linux);
+	assert_eq!(problems(&r, Dir::Cache), vec![Problem::NotADirectory]);
+
+	let _ = fs::remove_dir_all(&root);
+}
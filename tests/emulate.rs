@@ -0,0 +1,135 @@
+//! Tests for emulating other platforms' directories.
+//!
+//! These compute every platform's paths on whatever host runs the tests.
+
+use std::path::PathBuf;
+use sysdirs::emulate::Platform;
+use sysdirs::{Dir, Resolver};
+
+#[test]
+fn test_default_platform_is_current() {
+	assert_eq!(Resolver::from_process_env().platform(), Platform::current());
+}
+
+#[test]
+fn test_windows() {
+	let r = Resolver::from_env([
+		("USERPROFILE", r"C:\Users\Alice"),
+		("APPDATA", r"C:\Users\Alice\AppData\Roaming"),
+		("LOCALAPPDATA", r"C:\Users\Alice\AppData\Local"),
+		("ProgramData", r"C:\ProgramData"),
+		("PUBLIC", r"C:\Users\Public"),
+		("TEMP", r"C:\Users\Alice\AppData\Local\Temp"),
+	])
+	.with_platform(Platform::Windows);
+
+	assert_eq!(
+		r.cache_dir(),
+		Some(PathBuf::from(r"C:\Users\Alice\AppData\Local"))
+	);
+	assert_eq!(
+		r.config_dir(),
+		Some(PathBuf::from(r"C:\Users\Alice\AppData\Roaming"))
+	);
+	assert_eq!(
+		r.download_dir(),
+		Some(PathBuf::from(r"C:\Users\Alice\Downloads"))
+	);
+	assert_eq!(
+		r.template_dir(),
+		Some(PathBuf::from(
+			r"C:\Users\Alice\AppData\Roaming\Microsoft\Windows\Templates"
+		))
+	);
+	assert_eq!(r.public_dir(), Some(PathBuf::from(r"C:\Users\Public")));
+	assert_eq!(r.state_dir(), None);
+	assert_eq!(r.config_dirs(), vec![PathBuf::from(r"C:\ProgramData")]);
+}
+
+#[test]
+fn test_android() {
+	let r = Resolver::from_env(Vec::<(String, String)>::new())
+		.with_android_dirs(
+			"/data/data/com.example.app/files",
+			"/data/data/com.example.app/cache",
+		)
+		.with_platform(Platform::Android);
+
+	assert_eq!(
+		r.data_dir(),
+		Some(PathBuf::from("/data/data/com.example.app/files"))
+	);
+	assert_eq!(
+		r.cache_dir(),
+		Some(PathBuf::from("/data/data/com.example.app/cache"))
+	);
+	assert_eq!(r.audio_dir(), None);
+	assert!(r.data_dirs().is_empty());
+}
+
+#[test]
+#[cfg(not(target_os = "android"))]
+fn test_android_without_init_data() {
+	let r = Resolver::from_env([("HOME", "/home/alice")]).with_platform(Platform::Android);
+	assert_eq!(r.home_dir(), None);
+	assert_eq!(r.cache_dir(), None);
+}
+
+#[test]
+fn test_wasm_is_empty() {
+	let r = Resolver::from_env([("HOME", "/home/alice")]).with_platform(Platform::Wasm);
+	for &dir in Dir::ALL {
+		assert_eq!(r.get(dir), None, "{dir}");
+	}
+}
+
+#[test]
+#[cfg(unix)]
+fn test_linux_and_unix() {
+	let env = [("HOME", "/home/alice"), ("XDG_DATA_HOME", "/srv/data")];
+	let linux = Resolver::from_env(env).with_platform(Platform::Linux);
+	let unix = Resolver::from_env(env).with_platform(Platform::Unix);
+
+	for r in [&linux, &unix] {
+		assert_eq!(r.data_dir(), Some(PathBuf::from("/srv/data")));
+		assert_eq!(r.font_dir(), Some(PathBuf::from("/srv/data/fonts")));
+		assert_eq!(r.get(Dir::Cache), Some(PathBuf::from("/home/alice/.cache")));
+	}
+}
+
+#[test]
+fn test_xdg_values_are_absolute_on_any_host() {
+	let env = [
+		("XDG_CONFIG_HOME", "/srv/config"),
+		("XDG_DATA_DIRS", "/opt/share:relative"),
+	];
+	let linux = Resolver::from_env(env).with_platform(Platform::Linux);
+	let unix = Resolver::from_env(env).with_platform(Platform::Unix);
+
+	for r in [&linux, &unix] {
+		assert_eq!(r.config_dir(), Some(PathBuf::from("/srv/config")));
+		assert_eq!(r.data_dirs(), vec![PathBuf::from("/opt/share")]);
+		assert_eq!(r.config_dirs(), vec![PathBuf::from("/etc/xdg")]);
+	}
+}
+
+#[test]
+#[cfg(unix)]
+fn test_ios_sandbox() {
+	let r = Resolver::from_env([("HOME", "/var/mobile/Containers/Data/Application/ABC")])
+		.with_platform(Platform::Ios);
+
+	assert_eq!(
+		r.cache_dir(),
+		Some(PathBuf::from(
+			"/var/mobile/Containers/Data/Application/ABC/Library/Caches"
+		))
+	);
+	assert_eq!(
+		r.document_dir(),
+		Some(PathBuf::from(
+			"/var/mobile/Containers/Data/Application/ABC/Documents"
+		))
+	);
+	assert_eq!(r.download_dir(), None);
+}
@@ -0,0 +1,140 @@
+//! Tests for explain() and the sources it reports.
+//!
+//! These use emulated platforms, so they run the same on every host.
+
+use std::ffi::OsString;
+use std::path::Path;
+use sysdirs::emulate::Platform;
+use sysdirs::{Dir, DirError, Resolver, SearchPathDomain, Source};
+
+#[test]
+fn test_linux_sources() {
+	let r = Resolver::from_env([
+		("HOME", "/home/alice"),
+		("XDG_CACHE_HOME", "/var/cache/alice"),
+	])
+	.with_platform(Platform::Linux);
+
+	let cache = r.explain(Dir::Cache);
+	assert_eq!(cache.dir(), Dir::Cache);
+	assert_eq!(cache.path(), Some(Path::new("/var/cache/alice")));
+	assert_eq!(
+		cache.source(),
+		&Source::EnvVar {
+			var: "XDG_CACHE_HOME".to_string(),
+			value: "/var/cache/alice".into(),
+		}
+	);
+
+	// Derived directories keep the source of the one they're derived from
+	let font = r.explain(Dir::Font);
+	assert_eq!(
+		font.path(),
+		Some(Path::new("/home/alice/.local/share/fonts"))
+	);
+	assert_eq!(
+		font.source(),
+		&Source::XdgDefault {
+			home: "/home/alice".into()
+		}
+	);
+
+	assert_eq!(
+		r.explain(Dir::Home).source(),
+		&Source::EnvVar {
+			var: "HOME".to_string(),
+			value: "/home/alice".into(),
+		}
+	);
+	assert_eq!(r.explain(Dir::Temp).source(), &Source::PlatformDefault);
+}
+
+#[test]
+fn test_injected_home() {
+	let r = Resolver::from_env(Vec::<(String, OsString)>::new())
+		.with_home("/home/bob")
+		.with_platform(Platform::Windows);
+	assert_eq!(r.explain(Dir::Home).source(), &Source::Injected);
+
+	let audio = r.explain(Dir::Audio);
+	assert_eq!(audio.source(), &Source::Injected);
+	assert!(audio.path().is_some());
+}
+
+#[test]
+fn test_windows_sources() {
+	let r = Resolver::from_env([("APPDATA", r"C:\Users\Alice\AppData\Roaming")])
+		.with_platform(Platform::Windows);
+	let template = r.explain(Dir::Template);
+	assert_eq!(
+		template.source(),
+		&Source::EnvVar {
+			var: "APPDATA".to_string(),
+			value: r"C:\Users\Alice\AppData\Roaming".into(),
+		}
+	);
+}
+
+#[test]
+fn test_apple_sysdir_domain() {
+	let r = Resolver::from_env([("HOME", "/Users/alice")]).with_platform(Platform::MacOs);
+	assert_eq!(
+		r.explain(Dir::Cache).source(),
+		&Source::Sysdir {
+			domain: SearchPathDomain::User
+		}
+	);
+}
+
+#[test]
+fn test_android_injected() {
+	let r = Resolver::from_env(Vec::<(String, OsString)>::new())
+		.with_android_dirs(
+			"/data/user/0/com.example/files",
+			"/data/user/0/com.example/cache",
+		)
+		.with_platform(Platform::Android);
+	assert_eq!(r.explain(Dir::Cache).source(), &Source::Injected);
+}
+
+#[test]
+fn test_missing() {
+	let r = Resolver::from_env(Vec::<(String, OsString)>::new()).with_platform(Platform::Wasm);
+	let home = r.explain(Dir::Home);
+	assert_eq!(home.path(), None);
+	assert_eq!(
+		home.source(),
+		&Source::Missing(DirError::Unsupported {
+			platform: Platform::Wasm
+		})
+	);
+	assert_eq!(
+		home.to_string(),
+		"home: missing: directory is not available on wasm"
+	);
+}
+
+#[test]
+fn test_empty_home_is_unset() {
+	let r = Resolver::from_env([("HOME", "")]).with_platform(Platform::Linux);
+	assert_eq!(r.try_get(Dir::Home), Err(DirError::HomeNotSet));
+	assert_eq!(r.try_get(Dir::Cache), Err(DirError::HomeNotSet));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_passwd_fallback() {
+	let r = Resolver::from_env(Vec::<(String, OsString)>::new());
+	assert_eq!(r.try_get(Dir::Home), Err(DirError::HomeNotSet));
+
+	let r = r.with_passwd_fallback(true);
+	let home = r.explain(Dir::Home);
+	assert!(matches!(home.source(), Source::Passwd { .. }));
+	let home = home.path().unwrap();
+	assert!(home.is_absolute());
+	assert_eq!(r.cache_dir(), Some(home.join(".cache")));
+
+	// Never used when emulating another platform
+	let r = r.with_platform(Platform::Unix);
+	assert_eq!(r.try_get(Dir::Home), Err(DirError::HomeNotSet));
+}
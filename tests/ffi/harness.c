@@ -0,0 +1,64 @@
+/*
+ * Checks the C interface against a fixed environment. Run with tests/ffi/run.sh.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "sysdirs.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                            \
+	do {                                                                       \
+		if (!(cond)) {                                                         \
+			fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+			failures++;                                                        \
+		}                                                                      \
+	} while (0)
+
+int main(void) {
+	char buf[64];
+
+	/* Querying the length */
+	CHECK(sysdirs_get(SYSDIRS_HOME, NULL, 0) == (ptrdiff_t)strlen("/home/test"));
+
+	/* A buffer with room for the path and its NUL */
+	CHECK(sysdirs_get(SYSDIRS_HOME, buf, sizeof buf) == 10);
+	CHECK(strcmp(buf, "/home/test") == 0);
+
+	/* An exact fit */
+	char exact[11];
+	CHECK(sysdirs_get(SYSDIRS_HOME, exact, sizeof exact) == 10);
+	CHECK(strcmp(exact, "/home/test") == 0);
+
+	/* One byte short: nothing is written */
+	char small[10];
+	memset(small, 'x', sizeof small);
+	CHECK(sysdirs_get(SYSDIRS_HOME, small, sizeof small) == 10);
+	CHECK(memchr(small, '\0', sizeof small) == NULL);
+
+	/* Env vars are honoured */
+	CHECK(sysdirs_get(SYSDIRS_CACHE, buf, sizeof buf) == 10);
+	CHECK(strcmp(buf, "/xdg/cache") == 0);
+	CHECK(sysdirs_get(SYSDIRS_CONFIG, buf, sizeof buf) > 0);
+	CHECK(strcmp(buf, "/home/test/.config") == 0);
+
+	/* Errors */
+	CHECK(sysdirs_get(SYSDIRS_RUNTIME, buf, sizeof buf) == SYSDIRS_ERR_ENV_VAR_NOT_SET);
+	CHECK(sysdirs_get(SYSDIRS_LIBRARY, buf, sizeof buf) == SYSDIRS_ERR_UNSUPPORTED);
+	CHECK(sysdirs_get(-1, buf, sizeof buf) == SYSDIRS_ERR_INVALID_ARGUMENT);
+	CHECK(sysdirs_get(SYSDIRS_LIBRARY + 1, buf, sizeof buf) == SYSDIRS_ERR_INVALID_ARGUMENT);
+	CHECK(sysdirs_get(SYSDIRS_HOME, NULL, 8) == SYSDIRS_ERR_INVALID_ARGUMENT);
+
+	CHECK(strcmp(sysdirs_error_message(SYSDIRS_ERR_INVALID_ARGUMENT), "invalid argument") == 0);
+	CHECK(sysdirs_error_message(-100) != NULL);
+
+	if (failures > 0) {
+		fprintf(stderr, "%d check(s) failed\n", failures);
+		return EXIT_FAILURE;
+	}
+	printf("ffi harness: ok\n");
+	return EXIT_SUCCESS;
+}
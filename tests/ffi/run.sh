@@ -0,0 +1,11 @@
+#!/bin/sh
+# Builds the static library and runs the C harness against it (Linux).
+set -eu
+
+cd "$(dirname "$0")/../.."
+
+cargo rustc --release --lib --features ffi --crate-type staticlib
+cc -std=c99 -Wall -Wextra -Werror -Iinclude tests/ffi/harness.c \
+	target/release/libsysdirs.a -lpthread -ldl -lm -o target/ffi-harness
+
+env -i HOME=/home/test XDG_CACHE_HOME=/xdg/cache target/ffi-harness
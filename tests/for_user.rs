@@ -0,0 +1,57 @@
+//! Tests for for_user() and for_uid().
+//!
+//! These look up root, which every Linux system has.
+
+#![cfg(target_os = "linux")]
+
+use std::path::PathBuf;
+use sysdirs::emulate::Platform;
+use sysdirs::{Dir, Resolver, Source};
+
+#[test]
+fn test_for_uid_reads_passwd() {
+	let root = sysdirs::for_uid(0).unwrap();
+	let home = root.explain(Dir::Home);
+	assert_eq!(home.source(), &Source::Passwd { uid: 0 });
+
+	// The calling process's XDG variables are ignored
+	let home = home.path().unwrap();
+	assert_eq!(root.config_dir(), Some(home.join(".config")));
+	assert_eq!(root.cache_dir(), Some(home.join(".cache")));
+}
+
+#[test]
+fn test_for_user_matches_for_uid() {
+	let by_name = sysdirs::for_user("root").unwrap();
+	let by_uid = sysdirs::for_uid(0).unwrap();
+	assert_eq!(by_name.home_dir(), by_uid.home_dir());
+}
+
+#[test]
+fn test_unknown_user() {
+	assert!(sysdirs::for_user("no-such-user-sysdirs").is_none());
+	assert!(sysdirs::for_uid(u32::MAX - 1).is_none());
+}
+
+#[test]
+fn test_tilde_user_follows_passwd_fallback() {
+	let vars = [("HOME", "/home/alice"), ("XDG_CACHE_HOME", "~root/cache")];
+
+	// Injected environments don't consult the host's password database
+	let injected = Resolver::from_env(vars);
+	assert_eq!(
+		injected.cache_dir(),
+		Some(PathBuf::from("/home/alice/.cache"))
+	);
+
+	let root = sysdirs::for_uid(0).unwrap().home_dir().unwrap();
+	let opted_in = Resolver::from_env(vars).with_passwd_fallback(true);
+	assert_eq!(opted_in.cache_dir(), Some(root.join("cache")));
+
+	// Nor do emulated platforms
+	let emulated = opted_in.with_platform(Platform::Unix);
+	assert_eq!(
+		emulated.cache_dir(),
+		Some(PathBuf::from("/home/alice/.cache"))
+	);
+}
@@ -0,0 +1,98 @@
+//! Tests for the process-wide override registry.
+//!
+//! Overrides are global, so every test holds LOCK while it runs.
+
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+use sysdirs::{Dir, Resolver};
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[test]
+fn test_set_and_clear_override() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+	let original = sysdirs::data_dir();
+
+	assert_eq!(sysdirs::set_override(Dir::Data, "/srv/data"), None);
+	assert_eq!(sysdirs::data_dir(), Some(PathBuf::from("/srv/data")));
+	assert_eq!(sysdirs::get(Dir::Data), Some(PathBuf::from("/srv/data")));
+	assert_eq!(
+		Resolver::from_process_env().data_dir(),
+		Some(PathBuf::from("/srv/data"))
+	);
+
+	assert_eq!(
+		sysdirs::clear_override(Dir::Data),
+		Some(PathBuf::from("/srv/data"))
+	);
+	assert_eq!(sysdirs::data_dir(), original);
+}
+
+#[test]
+fn test_override_applies_to_unsupported_dirs() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+	// Library is None outside Apple platforms, but an override still wins
+	sysdirs::set_override(Dir::Library, "/opt/library");
+	assert_eq!(sysdirs::library_dir(), Some(PathBuf::from("/opt/library")));
+	sysdirs::clear_override(Dir::Library);
+}
+
+#[test]
+fn test_custom_resolver_ignores_overrides() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+	let resolver = Resolver::from_env([("HOME", "/home/alice")]);
+	let expected = resolver.cache_dir();
+
+	sysdirs::set_override(Dir::Cache, "/srv/cache");
+	assert_eq!(resolver.cache_dir(), expected);
+	sysdirs::clear_override(Dir::Cache);
+}
+
+#[test]
+fn test_clear_all_overrides() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+	let original = (sysdirs::config_dir(), sysdirs::state_dir());
+
+	sysdirs::set_override(Dir::Config, "/srv/config");
+	sysdirs::set_override(Dir::State, "/srv/state");
+	sysdirs::clear_all_overrides();
+
+	assert_eq!((sysdirs::config_dir(), sysdirs::state_dir()), original);
+}
+
+#[test]
+fn test_scoped_override_restores_previous() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+	let original = sysdirs::config_dir();
+
+	{
+		let _outer = sysdirs::scoped_override(Dir::Config, "/srv/outer");
+		{
+			let _inner = sysdirs::scoped_override(Dir::Config, "/srv/inner");
+			assert_eq!(sysdirs::config_dir(), Some(PathBuf::from("/srv/inner")));
+		}
+		assert_eq!(sysdirs::config_dir(), Some(PathBuf::from("/srv/outer")));
+	}
+	assert_eq!(sysdirs::config_dir(), original);
+}
+
+#[test]
+fn test_app_dirs_follow_overrides() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+	let _guard = sysdirs::scoped_override(Dir::Cache, "/srv/cache");
+
+	let app = sysdirs::AppDirs::new("com", "Foo Corp", "Bar App");
+	let cache = app.cache_dir().unwrap();
+	assert!(cache.starts_with("/srv/cache"), "{cache:?}");
+}
+
+#[test]
+fn test_explain_reports_override() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+	let _guard = sysdirs::scoped_override(Dir::Data, "/srv/data");
+
+	let resolution = sysdirs::explain(Dir::Data);
+	assert_eq!(resolution.path(), Some(std::path::Path::new("/srv/data")));
+	assert_eq!(resolution.source(), &sysdirs::Source::Override);
+}
@@ -1,7 +1,7 @@
 //! Tests for PathExt trait.
 
 use std::path::PathBuf;
-use sysdirs::PathExt;
+use sysdirs::{DirError, PathExt};
 
 #[test]
 fn test_join_chains() {
@@ -81,3 +81,233 @@ fn test_real_dirs_with_pathext() {
 	#[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
 	assert!(path.unwrap().ends_with("test-app"));
 }
+
+#[test]
+fn test_result_join_and_ensure_error_carries_reason() {
+	let missing: Result<PathBuf, DirError> = Err(DirError::HomeNotSet);
+	let err = missing.join("my-app").ensure().unwrap_err();
+
+	assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+	let source = err.into_inner().unwrap().downcast::<DirError>().unwrap();
+	assert_eq!(*source, DirError::HomeNotSet);
+}
+
+#[test]
+fn test_result_join_on_ok() {
+	let path: Result<PathBuf, DirError> = Ok(PathBuf::from("/base"));
+	assert_eq!(path.join("a").join("b"), Ok(PathBuf::from("/base/a/b")));
+}
+
+/// An empty directory under the temp directory with the given mode.
+#[cfg(unix)]
+fn fresh_dir(name: &str, mode: u32) -> PathBuf {
+	use std::fs;
+	use std::os::unix::fs::PermissionsExt;
+
+	let dir = std::env::temp_dir().join(name);
+	let _ = fs::remove_dir_all(&dir);
+	fs::create_dir(&dir).unwrap();
+	fs::set_permissions(&dir, fs::Permissions::from_mode(mode)).unwrap();
+	dir
+}
+
+#[cfg(unix)]
+mod modes {
+	use super::fresh_dir;
+	use std::fs;
+	use std::os::unix::fs::PermissionsExt;
+	use std::path::Path;
+	use sysdirs::PathExt;
+
+	fn mode(path: &Path) -> u32 {
+		fs::metadata(path).unwrap().permissions().mode() & 0o777
+	}
+
+	#[test]
+	fn test_ensure_private_creates_every_missing_dir_0700() {
+		let base = fresh_dir("sysdirs-test-ensure-private", 0o755);
+
+		let path = Some(base.clone())
+			.join("a")
+			.join("b")
+			.ensure_private()
+			.unwrap();
+
+		assert_eq!(mode(&base.join("a")), 0o700);
+		assert_eq!(mode(&path), 0o700);
+		// The existing parent is left alone
+		assert_eq!(mode(&base), 0o755);
+
+		let _ = fs::remove_dir_all(&base);
+	}
+
+	#[test]
+	fn test_ensure_with_mode_ignores_umask() {
+		let base = fresh_dir("sysdirs-test-ensure-mode", 0o755);
+
+		// Group write is cleared by the usual 022 umask
+		let path = Some(base.join("shared")).ensure_with_mode(0o770).unwrap();
+		assert_eq!(mode(&path), 0o770);
+
+		let _ = fs::remove_dir_all(&base);
+	}
+
+	#[test]
+	fn test_ensure_private_rejects_existing_looser_dir() {
+		let base = fresh_dir("sysdirs-test-ensure-looser", 0o755);
+
+		let err = Some(base.clone()).ensure_private().unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+		// Nothing was changed
+		assert_eq!(mode(&base), 0o755);
+
+		// Stricter than asked for is fine
+		fs::set_permissions(&base, fs::Permissions::from_mode(0o700)).unwrap();
+		assert_eq!(Some(base.clone()).ensure_with_mode(0o750).unwrap(), base);
+
+		let _ = fs::remove_dir_all(&base);
+	}
+
+	#[test]
+	fn test_ensure_private_rejects_file() {
+		let base = fresh_dir("sysdirs-test-ensure-file", 0o755);
+		fs::write(base.join("file"), "").unwrap();
+
+		let err = Some(base.join("file")).ensure_private().unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::NotADirectory);
+
+		let _ = fs::remove_dir_all(&base);
+	}
+}
+
+#[cfg(unix)]
+mod secure {
+	use super::fresh_dir;
+	use std::fs;
+	use std::os::unix::fs::{PermissionsExt, symlink};
+	use std::path::PathBuf;
+	use sysdirs::PathExt;
+
+	#[test]
+	fn test_ensure_secure_creates_private_dirs() {
+		let base = fresh_dir("sysdirs-test-secure-create", 0o755);
+
+		let path = Some(base.join("a").join("b")).ensure_secure().unwrap();
+		let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+		assert_eq!(mode, 0o700);
+
+		// Running again accepts the directory it created
+		assert!(Some(path).ensure_secure().is_ok());
+
+		let _ = fs::remove_dir_all(&base);
+	}
+
+	#[test]
+	fn test_ensure_secure_refuses_symlink_in_shared_dir() {
+		// Sticky and world-writable, like /tmp
+		let base = fresh_dir("sysdirs-test-secure-shared", 0o1777);
+		let target = base.join("target");
+		fs::create_dir(&target).unwrap();
+		symlink(&target, base.join("app")).unwrap();
+
+		let err = Some(base.join("app").join("sub"))
+			.ensure_secure()
+			.unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+		assert!(!target.join("sub").exists());
+
+		let _ = fs::remove_dir_all(&base);
+	}
+
+	#[test]
+	fn test_ensure_secure_follows_symlink_in_private_dir() {
+		let base = fresh_dir("sysdirs-test-secure-private", 0o755);
+		let target = base.join("target");
+		fs::create_dir(&target).unwrap();
+		symlink(&target, base.join("app")).unwrap();
+
+		assert!(Some(base.join("app").join("sub")).ensure_secure().is_ok());
+		assert!(target.join("sub").is_dir());
+
+		let _ = fs::remove_dir_all(&base);
+	}
+
+	#[test]
+	fn test_ensure_secure_rejects_relative_and_dotdot() {
+		let err = Some(PathBuf::from("relative/dir"))
+			.ensure_secure()
+			.unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+		let err = Some(std::env::temp_dir().join("..").join("x"))
+			.ensure_secure()
+			.unwrap_err();
+		assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+	}
+}
+
+#[cfg(unix)]
+mod runtime {
+	use super::fresh_dir;
+	use std::fs;
+	use std::os::unix::fs::{MetadataExt, PermissionsExt};
+	use std::path::Path;
+	use std::sync::{Mutex, PoisonError};
+	use sysdirs::{Dir, RuntimeDir};
+
+	// The temp directory is pointed at a fixture with a process-wide override
+	static LOCK: Mutex<()> = Mutex::new(());
+
+	/// Runs `runtime_dir_or_fallback()` with the temp directory at `temp`, or returns
+	/// `None` if `$XDG_RUNTIME_DIR` is set and the fallback can't be reached.
+	fn fallback_under(temp: &Path) -> Option<std::io::Result<RuntimeDir>> {
+		if let Some(real) = sysdirs::runtime_dir() {
+			assert_eq!(
+				sysdirs::runtime_dir_or_fallback().unwrap(),
+				RuntimeDir::Xdg(real)
+			);
+			return None;
+		}
+		sysdirs::set_override(Dir::Temp, temp);
+		let result = sysdirs::runtime_dir_or_fallback();
+		sysdirs::clear_override(Dir::Temp);
+		Some(result)
+	}
+
+	#[test]
+	fn test_fallback_is_created_private() {
+		let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+		let temp = fresh_dir("sysdirs-test-runtime-fallback", 0o755);
+
+		if let Some(dir) = fallback_under(&temp) {
+			let dir = dir.unwrap();
+			assert!(dir.is_fallback());
+			let uid = fs::metadata(&temp).unwrap().uid();
+			assert_eq!(dir.path(), temp.join(format!("runtime-{uid}")));
+			let mode = fs::metadata(dir.path()).unwrap().permissions().mode() & 0o777;
+			assert_eq!(mode, 0o700);
+
+			// The second call validates the existing directory
+			assert_eq!(fallback_under(&temp).unwrap().unwrap(), dir);
+		}
+
+		let _ = fs::remove_dir_all(&temp);
+	}
+
+	#[test]
+	fn test_fallback_with_loose_mode_is_rejected() {
+		let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+		let temp = fresh_dir("sysdirs-test-runtime-loose", 0o755);
+		let uid = fs::metadata(&temp).unwrap().uid();
+		let path = temp.join(format!("runtime-{uid}"));
+		fs::create_dir(&path).unwrap();
+		fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+		if let Some(result) = fallback_under(&temp) {
+			let err = result.unwrap_err();
+			assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+		}
+
+		let _ = fs::remove_dir_all(&temp);
+	}
+}
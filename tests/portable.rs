@@ -0,0 +1,75 @@
+//! Tests for portable mode.
+//!
+//! Portable mode is global, so every test holds LOCK while it runs.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+use sysdirs::{Dir, PortableMode};
+
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn exe_dir() -> PathBuf {
+	std::env::current_exe()
+		.unwrap()
+		.parent()
+		.unwrap()
+		.to_path_buf()
+}
+
+#[test]
+fn test_enabled_redirects_next_to_exe() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+	let root = exe_dir();
+	let home = sysdirs::home_dir();
+
+	sysdirs::set_portable_mode(PortableMode::Enabled);
+	assert!(sysdirs::is_portable());
+	assert_eq!(sysdirs::portable_dir(), Some(root.clone()));
+	assert_eq!(sysdirs::config_dir(), Some(root.join("config")));
+	assert_eq!(sysdirs::data_dir(), Some(root.join("data")));
+	assert_eq!(sysdirs::cache_dir(), Some(root.join("cache")));
+	assert_eq!(sysdirs::state_dir(), Some(root.join("state")));
+	assert_eq!(sysdirs::temp_dir(), Some(root.join("temp")));
+
+	// Directories outside the application's own aren't redirected
+	assert_eq!(sysdirs::home_dir(), home);
+
+	sysdirs::set_portable_mode(PortableMode::Auto);
+}
+
+#[test]
+fn test_marker_file() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+	let marker = format!("sysdirs-test-portable-{}.txt", std::process::id());
+	let marker_path = exe_dir().join(&marker);
+
+	sysdirs::set_portable_mode(PortableMode::Auto);
+	sysdirs::set_portable_marker(marker.as_str());
+	assert!(!sysdirs::is_portable());
+
+	std::fs::write(&marker_path, "").unwrap();
+	sysdirs::set_portable_marker(marker.as_str());
+	assert!(sysdirs::is_portable());
+	assert_eq!(sysdirs::get(Dir::Cache), Some(exe_dir().join("cache")));
+
+	sysdirs::set_portable_mode(PortableMode::Disabled);
+	assert!(!sysdirs::is_portable());
+
+	std::fs::remove_file(&marker_path).unwrap();
+	sysdirs::set_portable_mode(PortableMode::Auto);
+	sysdirs::set_portable_marker(sysdirs::DEFAULT_PORTABLE_MARKER);
+}
+
+#[test]
+fn test_override_beats_portable() {
+	let _lock = LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+	sysdirs::set_portable_mode(PortableMode::Enabled);
+	let _guard = sysdirs::scoped_override(Dir::Data, "/srv/data");
+	assert_eq!(sysdirs::data_dir(), Some(PathBuf::from("/srv/data")));
+	assert_eq!(sysdirs::cache_dir(), Some(exe_dir().join("cache")));
+
+	sysdirs::set_portable_mode(PortableMode::Auto);
+}
@@ -0,0 +1,114 @@
+//! Tests for Resolver with an injected environment.
+//!
+//! These run in parallel without touching the process environment.
+
+use std::ffi::OsString;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use sysdirs::{Dir, Resolver};
+
+#[test]
+#[cfg(not(target_arch = "wasm32"))]
+fn test_home_override() {
+	let resolver = Resolver::from_env(Vec::<(String, OsString)>::new()).with_home("/home/alice");
+	assert_eq!(resolver.home_dir(), Some(PathBuf::from("/home/alice")));
+}
+
+#[test]
+fn test_get_matches_methods() {
+	let resolver = Resolver::from_env([("HOME", "/home/alice")]);
+	for (dir, path) in resolver.all() {
+		assert_eq!(resolver.get(dir), path);
+	}
+	assert_eq!(resolver.get(Dir::Cache), resolver.cache_dir());
+	assert_eq!(resolver.get(Dir::Config), resolver.config_dir());
+}
+
+#[test]
+fn test_from_process_env_matches_free_functions() {
+	let resolver = Resolver::from_process_env();
+	assert_eq!(resolver.home_dir(), sysdirs::home_dir());
+	assert_eq!(resolver.cache_dir(), sysdirs::cache_dir());
+	assert_eq!(resolver.data_dirs(), sysdirs::data_dirs());
+}
+
+#[test]
+fn test_from_fn_lookup() {
+	let resolver = Resolver::from_fn(|key| match key {
+		"HOME" => Some("/home/bob".into()),
+		_ => None,
+	});
+	assert_eq!(resolver.var_os("HOME"), Some(OsString::from("/home/bob")));
+	assert_eq!(resolver.var_os("XDG_CACHE_HOME"), None);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_linux_xdg_from_map() {
+	let resolver = Resolver::from_env([
+		("HOME", "/home/alice"),
+		("XDG_CACHE_HOME", "/var/cache/alice"),
+		("XDG_CONFIG_HOME", ""),
+		("XDG_DATA_DIRS", "/opt/share:/usr/share"),
+	]);
+
+	assert_eq!(resolver.home_dir(), Some(PathBuf::from("/home/alice")));
+	assert_eq!(
+		resolver.cache_dir(),
+		Some(PathBuf::from("/var/cache/alice"))
+	);
+	assert_eq!(
+		resolver.config_dir(),
+		Some(PathBuf::from("/home/alice/.config"))
+	);
+	assert_eq!(
+		resolver.font_dir(),
+		Some(PathBuf::from("/home/alice/.local/share/fonts"))
+	);
+	assert_eq!(resolver.runtime_dir(), None);
+	assert_eq!(resolver.temp_dir(), Some(PathBuf::from("/tmp")));
+	assert_eq!(
+		resolver.data_dirs(),
+		vec![PathBuf::from("/opt/share"), PathBuf::from("/usr/share")]
+	);
+	assert_eq!(resolver.config_dirs(), vec![PathBuf::from("/etc/xdg")]);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_linux_home_override_wins_over_env() {
+	let resolver = Resolver::from_env([("HOME", "/home/alice")]).with_home("/home/bob");
+	assert_eq!(
+		resolver.cache_dir(),
+		Some(PathBuf::from("/home/bob/.cache"))
+	);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_linux_user_dirs_file() {
+	let root = std::env::temp_dir().join("sysdirs-test-resolver-user-dirs");
+	let _ = std::fs::remove_dir_all(&root);
+	std::fs::create_dir_all(root.join("config")).unwrap();
+	std::fs::write(
+		root.join("config/user-dirs.dirs"),
+		"XDG_MUSIC_DIR=\"$HOME/Tunes\"\nXDG_VIDEOS_DIR=\"$HOME/\"\n",
+	)
+	.unwrap();
+
+	let resolver = Resolver::from_env([
+		("XDG_CONFIG_HOME", root.join("config").into_os_string()),
+		("XDG_DESKTOP_DIR", "/srv/desktop".into()),
+	])
+	.with_home("/home/alice");
+
+	assert_eq!(
+		resolver.audio_dir(),
+		Some(PathBuf::from("/home/alice/Tunes"))
+	);
+	assert_eq!(resolver.video_dir(), None);
+	// Env vars take precedence over the file
+	assert_eq!(resolver.desktop_dir(), Some(PathBuf::from("/srv/desktop")));
+
+	let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,57 @@
+//! Tests for secure mode.
+//!
+//! Secure mode is process-wide, so every test here runs with it on.
+
+#![cfg(target_os = "linux")]
+
+use std::path::PathBuf;
+use sysdirs::emulate::Platform;
+use sysdirs::{Dir, Resolver, Source};
+
+fn secure() -> Resolver {
+	sysdirs::set_secure_mode(true);
+	assert!(sysdirs::is_secure_execution());
+	Resolver::from_process_env()
+}
+
+#[test]
+fn test_process_env_is_ignored() {
+	let r = secure();
+	assert_eq!(r.var_os("PATH"), None);
+	assert_eq!(r.temp_dir(), Some(PathBuf::from("/tmp")));
+	assert_eq!(r.runtime_dir(), None);
+}
+
+#[test]
+fn test_apple_env_is_ignored() {
+	let r = secure().with_platform(Platform::MacOs);
+	assert_eq!(r.var_os("TMPDIR"), None);
+	assert_eq!(r.temp_dir(), Some(PathBuf::from("/tmp")));
+}
+
+#[test]
+fn test_home_comes_from_passwd() {
+	let r = secure();
+	let home = r.explain(Dir::Home);
+	let Source::Passwd { uid } = *home.source() else {
+		panic!("home came from {}", home.source());
+	};
+	let expected = sysdirs::for_uid(uid).unwrap();
+	assert_eq!(r.home_dir(), expected.home_dir());
+	assert_eq!(r.config_dir(), expected.config_dir());
+	assert_eq!(sysdirs::cache_dir(), expected.cache_dir());
+}
+
+#[test]
+fn test_injected_env_is_honored() {
+	let _ = secure();
+	let r = Resolver::from_env([("XDG_CACHE_HOME", "/tmp/cache")]).with_home("/home/alice");
+	assert_eq!(r.cache_dir(), Some(PathBuf::from("/tmp/cache")));
+	assert_eq!(r.home_dir(), Some(PathBuf::from("/home/alice")));
+}
+
+#[test]
+fn test_original_user_is_ignored() {
+	let _ = secure();
+	assert!(sysdirs::original_user().is_none());
+}
@@ -0,0 +1,81 @@
+//! Tests for Snapshot and freeze().
+//!
+//! Freezing lasts for the whole process, so only test_freeze calls it; the other
+//! tests capture from injected resolvers, which a frozen snapshot doesn't affect.
+
+use std::ffi::OsString;
+use std::path::Path;
+use sysdirs::emulate::Platform;
+use sysdirs::{Dir, DirError, Resolver, Snapshot, Source};
+
+fn linux(vars: &[(&str, &str)]) -> Resolver {
+	Resolver::from_env(vars.iter().copied()).with_platform(Platform::Linux)
+}
+
+#[test]
+fn test_snapshot_matches_resolver() {
+	let resolver = linux(&[("HOME", "/home/alice")]);
+	let snapshot = Snapshot::from_resolver(&resolver);
+
+	for (dir, path) in snapshot.iter() {
+		assert_eq!(path.map(Path::to_path_buf), resolver.get(dir), "{dir}");
+	}
+	assert_eq!(snapshot.data_dirs(), resolver.data_dirs().as_slice());
+	assert_eq!(snapshot.config_dirs(), resolver.config_dirs().as_slice());
+	assert_eq!(
+		snapshot.try_get(Dir::Runtime),
+		Err(&DirError::EnvVarNotSet {
+			var: "XDG_RUNTIME_DIR".to_string()
+		})
+	);
+}
+
+#[test]
+fn test_snapshot_equality() {
+	let before = Snapshot::from_resolver(&linux(&[("HOME", "/home/alice")]));
+	let clone = before.clone();
+	let same = Snapshot::from_resolver(&linux(&[("HOME", "/home/alice")]));
+	let moved = Snapshot::from_resolver(&linux(&[
+		("HOME", "/home/alice"),
+		("XDG_CACHE_HOME", "/var/cache/alice"),
+	]));
+
+	assert_eq!(before, clone);
+	assert_eq!(before, same);
+	assert_ne!(before, moved);
+}
+
+#[test]
+fn test_freeze() {
+	// An override active while freezing isn't baked into the snapshot
+	let guard = sysdirs::scoped_override(Dir::Cache, "/override/cache");
+	let snapshot = sysdirs::freeze();
+	assert_eq!(
+		sysdirs::cache_dir().as_deref(),
+		Some(Path::new("/override/cache"))
+	);
+	drop(guard);
+	assert_ne!(
+		sysdirs::cache_dir().as_deref(),
+		Some(Path::new("/override/cache"))
+	);
+	assert_ne!(snapshot.explain(Dir::Cache).source(), &Source::Override);
+
+	assert!(std::ptr::eq(snapshot, sysdirs::freeze()));
+	assert!(sysdirs::frozen().is_some());
+
+	for (dir, path) in snapshot.iter() {
+		assert_eq!(sysdirs::get(dir).as_deref(), path, "{dir}");
+	}
+
+	// Overrides still win over the frozen snapshot
+	let _guard = sysdirs::scoped_override(Dir::State, "/srv/state");
+	assert_eq!(
+		sysdirs::state_dir().as_deref(),
+		Some(Path::new("/srv/state"))
+	);
+
+	// Injected resolvers don't use it
+	let resolver = Resolver::from_env(Vec::<(String, OsString)>::new()).with_home("/home/bob");
+	assert_eq!(resolver.home_dir().as_deref(), Some(Path::new("/home/bob")));
+}